@@ -0,0 +1,200 @@
+use crate::interpreter::{
+    expressions::{self, Expr},
+    scanner::Literal,
+    statements::{self, FunctionStmt, Stmt},
+};
+use std::rc::Rc;
+
+/// Renders syntax trees as parenthesized prefix expressions, e.g.
+/// `print 1 + 2 * x;` becomes `(print (+ 1 (* 2 x)))`.
+pub struct AstPrinter;
+
+impl AstPrinter {
+    pub fn print_program(&mut self, statements: &[Stmt]) -> String {
+        statements
+            .iter()
+            .map(|statement| self.print_stmt(statement))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn print_stmt(&mut self, stmt: &Stmt) -> String {
+        stmt.accept(self)
+    }
+
+    pub fn print_expr(&mut self, expr: &Expr) -> String {
+        expr.accept(self)
+    }
+
+    fn parenthesize(&mut self, name: &str, exprs: &[&Expr]) -> String {
+        let mut out = format!("({name}");
+        for expr in exprs {
+            out.push(' ');
+            out.push_str(&self.print_expr(expr));
+        }
+        out.push(')');
+        out
+    }
+
+    fn print_function(&mut self, keyword: &str, function: &FunctionStmt) -> String {
+        let params = function
+            .params
+            .iter()
+            .map(|param| param.lexeme.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut out = format!("({keyword} {} ({params})", function.name.lexeme);
+        for statement in &function.body {
+            out.push(' ');
+            out.push_str(&self.print_stmt(statement));
+        }
+        out.push(')');
+        out
+    }
+}
+
+impl expressions::Visitor<String> for AstPrinter {
+    fn visit_binary_expr(&mut self, expr: &expressions::BinaryExpr) -> String {
+        self.parenthesize(&expr.operator.lexeme, &[&expr.left, &expr.right])
+    }
+
+    fn visit_unary_expr(&mut self, expr: &expressions::UnaryExpr) -> String {
+        self.parenthesize(&expr.operator.lexeme, &[&expr.right])
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &expressions::GroupingExpr) -> String {
+        self.parenthesize("group", &[&expr.expression])
+    }
+
+    fn visit_literal_expr(&mut self, expr: &expressions::LiteralExpr) -> String {
+        match &expr.value {
+            Literal::Int(n) => n.to_string(),
+            Literal::BigInt(n) => format!("{n}n"),
+            Literal::Decimal(d) => format!("{d}d"),
+            Literal::Number(n) => format!("{n:?}"),
+            Literal::Str(s) => format!("{s:?}"),
+            Literal::Bool(b) => b.to_string(),
+            Literal::Unknown(u) => u.clone(),
+            Literal::Nil => "nil".to_string(),
+        }
+    }
+
+    fn visit_variable_expr(&mut self, expr: &expressions::VariableExpr) -> String {
+        expr.name.lexeme.clone()
+    }
+
+    fn visit_assign_expr(&mut self, expr: &expressions::AssignExpr) -> String {
+        format!("(= {} {})", expr.name.lexeme, self.print_expr(&expr.value))
+    }
+
+    fn visit_logical_expr(&mut self, expr: &expressions::LogicalExpr) -> String {
+        self.parenthesize(&expr.operator.lexeme, &[&expr.left, &expr.right])
+    }
+
+    fn visit_call_expr(&mut self, expr: &expressions::CallExpr) -> String {
+        let mut exprs = vec![&expr.callee];
+        exprs.extend(expr.arguments.iter());
+        self.parenthesize("call", &exprs)
+    }
+
+    fn visit_get_expr(&mut self, expr: &expressions::GetExpr) -> String {
+        format!("(. {} {})", self.print_expr(&expr.object), expr.name.lexeme)
+    }
+
+    fn visit_set_expr(&mut self, expr: &expressions::SetExpr) -> String {
+        format!(
+            "(= (. {} {}) {})",
+            self.print_expr(&expr.object),
+            expr.name.lexeme,
+            self.print_expr(&expr.value)
+        )
+    }
+
+    fn visit_this_expr(&mut self, _expr: &expressions::ThisExpr) -> String {
+        "this".to_string()
+    }
+
+    fn visit_super_expr(&mut self, expr: &expressions::SuperExpr) -> String {
+        format!("(super {})", expr.method.lexeme)
+    }
+
+    fn visit_interpolation_expr(&mut self, expr: &expressions::InterpolationExpr) -> String {
+        let parts: Vec<&Expr> = expr.parts.iter().collect();
+        self.parenthesize("interpolate", &parts)
+    }
+}
+
+impl statements::Visitor<String> for AstPrinter {
+    fn visit_expression_stmt(&mut self, stmt: &statements::ExpressionStmt) -> String {
+        self.parenthesize(";", &[&stmt.expression])
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &statements::PrintStmt) -> String {
+        self.parenthesize("print", &[&stmt.expression])
+    }
+
+    fn visit_var_stmt(&mut self, stmt: &statements::VarStmt) -> String {
+        match &stmt.initializer {
+            Some(initializer) => format!(
+                "(var {} {})",
+                stmt.name.lexeme,
+                self.print_expr(initializer)
+            ),
+            None => format!("(var {})", stmt.name.lexeme),
+        }
+    }
+
+    fn visit_block_stmt(&mut self, stmt: &statements::BlockStmt) -> String {
+        let mut out = "(block".to_string();
+        for statement in &stmt.statements {
+            out.push(' ');
+            out.push_str(&self.print_stmt(statement));
+        }
+        out.push(')');
+        out
+    }
+
+    fn visit_if_stmt(&mut self, stmt: &statements::IfStmt) -> String {
+        let condition = self.print_expr(&stmt.condition);
+        let then_branch = self.print_stmt(&stmt.then_branch);
+        match &stmt.else_branch {
+            Some(else_branch) => format!(
+                "(if {condition} {then_branch} {})",
+                self.print_stmt(else_branch)
+            ),
+            None => format!("(if {condition} {then_branch})"),
+        }
+    }
+
+    fn visit_while_stmt(&mut self, stmt: &statements::WhileStmt) -> String {
+        format!(
+            "(while {} {})",
+            self.print_expr(&stmt.condition),
+            self.print_stmt(&stmt.body)
+        )
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Rc<FunctionStmt>) -> String {
+        self.print_function("fun", stmt)
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &statements::ReturnStmt) -> String {
+        match &stmt.value {
+            Some(value) => self.parenthesize("return", &[value]),
+            None => "(return)".to_string(),
+        }
+    }
+
+    fn visit_class_stmt(&mut self, stmt: &statements::ClassStmt) -> String {
+        let mut out = format!("(class {}", stmt.name.lexeme);
+        if let Some(superclass) = &stmt.superclass {
+            out.push_str(&format!(" < {}", self.print_expr(superclass)));
+        }
+        for method in &stmt.methods {
+            out.push(' ');
+            out.push_str(&self.print_function("method", method));
+        }
+        out.push(')');
+        out
+    }
+}
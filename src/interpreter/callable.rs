@@ -0,0 +1,143 @@
+use crate::interpreter::{
+    environment::Environment,
+    interpreter::{Interpreter, RuntimeError, Unwind, Value},
+    statements::FunctionStmt,
+};
+use std::{cell::RefCell, fmt, rc::Rc};
+
+/// How many arguments a callable accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Fixed(usize),
+    /// Any number of arguments, including none.
+    Variadic,
+}
+
+impl From<usize> for Arity {
+    fn from(count: usize) -> Self {
+        Arity::Fixed(count)
+    }
+}
+
+pub trait Callable {
+    fn name(&self) -> &str;
+    fn arity(&self) -> Arity;
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError>;
+}
+
+impl fmt::Debug for dyn Callable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<fn {}>", self.name())
+    }
+}
+
+pub struct KestrlFunction {
+    name: String,
+    declaration: Rc<FunctionStmt>,
+    closure: Rc<RefCell<Environment>>,
+    is_initializer: bool,
+}
+
+impl KestrlFunction {
+    pub fn new(
+        declaration: Rc<FunctionStmt>,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> Self {
+        KestrlFunction {
+            name: declaration.name.lexeme.clone(),
+            declaration,
+            closure,
+            is_initializer,
+        }
+    }
+
+    /// Creates a copy of this method whose closure binds `this` to `instance`.
+    pub fn bind(&self, instance: Value) -> KestrlFunction {
+        let mut environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        environment.define("this", instance);
+        KestrlFunction::new(
+            Rc::clone(&self.declaration),
+            Rc::new(RefCell::new(environment)),
+            self.is_initializer,
+        )
+    }
+
+    fn bound_this(&self) -> Value {
+        Environment::get_at(&self.closure, 0, "this")
+    }
+}
+
+impl Callable for KestrlFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Fixed(self.declaration.params.len())
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let mut environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        for (param, argument) in self.declaration.params.iter().zip(arguments) {
+            environment.define(&param.lexeme, argument);
+        }
+
+        match interpreter.execute_block(&self.declaration.body, Rc::new(RefCell::new(environment)))
+        {
+            Ok(()) | Err(Unwind::Return(_)) if self.is_initializer => Ok(self.bound_this()),
+            Ok(()) => Ok(Value::Nil),
+            Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Error(e)) => Err(e),
+        }
+    }
+}
+
+pub type NativeFn = dyn Fn(&[Value]) -> Result<Value, RuntimeError>;
+
+/// A function implemented in Rust. Errors it returns without a position are
+/// reported at the call site.
+pub struct NativeFunction {
+    name: String,
+    arity: Arity,
+    function: Box<NativeFn>,
+}
+
+impl NativeFunction {
+    pub fn new<F>(name: &str, arity: impl Into<Arity>, function: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value, RuntimeError> + 'static,
+    {
+        NativeFunction {
+            name: name.to_string(),
+            arity: arity.into(),
+            function: Box::new(function),
+        }
+    }
+}
+
+impl Callable for NativeFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        (self.function)(&arguments)
+    }
+}
@@ -0,0 +1,112 @@
+use crate::interpreter::{
+    callable::{Arity, Callable, KestrlFunction},
+    interpreter::{Interpreter, RuntimeError, Value},
+    scanner::Token,
+};
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+pub struct KestrlClass {
+    pub name: String,
+    pub superclass: Option<Rc<KestrlClass>>,
+    methods: HashMap<String, Rc<KestrlFunction>>,
+}
+
+impl KestrlClass {
+    pub fn new(
+        name: String,
+        superclass: Option<Rc<KestrlClass>>,
+        methods: HashMap<String, Rc<KestrlFunction>>,
+    ) -> Self {
+        KestrlClass {
+            name,
+            superclass,
+            methods,
+        }
+    }
+
+    /// Looks a method up on this class, falling back to the superclass chain.
+    pub fn find_method(&self, name: &str) -> Option<Rc<KestrlFunction>> {
+        match self.methods.get(name) {
+            Some(method) => Some(Rc::clone(method)),
+            None => self
+                .superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_method(name)),
+        }
+    }
+
+    pub fn arity(&self) -> Arity {
+        self.find_method("init")
+            .map_or(Arity::Fixed(0), |initializer| initializer.arity())
+    }
+
+    /// Calling a class creates a new instance and runs its `init` method, if any.
+    pub fn instantiate(
+        class: &Rc<KestrlClass>,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let instance =
+            Value::Instance(Rc::new(RefCell::new(KestrlInstance::new(Rc::clone(class)))));
+
+        if let Some(initializer) = class.find_method("init") {
+            initializer
+                .bind(instance.clone())
+                .call(interpreter, arguments)?;
+        }
+
+        Ok(instance)
+    }
+}
+
+impl fmt::Debug for KestrlClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<class {}>", self.name)
+    }
+}
+
+pub struct KestrlInstance {
+    pub class: Rc<KestrlClass>,
+    fields: HashMap<String, Value>,
+}
+
+impl KestrlInstance {
+    pub fn new(class: Rc<KestrlClass>) -> Self {
+        KestrlInstance {
+            class,
+            fields: HashMap::new(),
+        }
+    }
+
+    /// Fields shadow methods; methods are bound to the instance they were read from.
+    pub fn get(
+        instance: &Rc<RefCell<KestrlInstance>>,
+        name: &Token,
+    ) -> Result<Value, RuntimeError> {
+        let key = name.lexeme.as_str();
+        if let Some(value) = instance.borrow().fields.get(key) {
+            return Ok(value.clone());
+        }
+
+        let method = instance.borrow().class.find_method(key);
+        match method {
+            Some(method) => Ok(Value::Function(Rc::new(
+                method.bind(Value::Instance(Rc::clone(instance))),
+            ))),
+            None => Err(RuntimeError {
+                span: Some(name.span),
+                message: format!("Undefined property '{key}'."),
+            }),
+        }
+    }
+
+    pub fn set(&mut self, name: &Token, value: Value) {
+        self.fields.insert(name.lexeme.clone(), value);
+    }
+}
+
+impl fmt::Debug for KestrlInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{} instance>", self.class.name)
+    }
+}
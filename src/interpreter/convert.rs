@@ -0,0 +1,200 @@
+//! Conversions between Rust types and Kestrl [`Value`]s, used to give
+//! native functions typed arguments and results.
+
+use crate::interpreter::{
+    bigint::BigInt,
+    callable::Arity,
+    decimal::Decimal,
+    interpreter::{RuntimeError, Value},
+};
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Int(n)
+    }
+}
+
+impl From<BigInt> for Value {
+    fn from(n: BigInt) -> Self {
+        Value::BigInt(n)
+    }
+}
+
+impl From<Decimal> for Value {
+    fn from(d: Decimal) -> Self {
+        Value::Decimal(d)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Boolean(b)
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Value::Nil
+    }
+}
+
+/// `None` becomes `nil`.
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Nil, Into::into)
+    }
+}
+
+/// Rust types that can be read out of a Kestrl value.
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError>;
+}
+
+fn mismatch(expected: &str, value: &Value) -> RuntimeError {
+    RuntimeError::new(format!(
+        "Expected {expected} but got {}.",
+        match value {
+            Value::Nil => "nil".to_string(),
+            Value::Int(_) => "an integer".to_string(),
+            Value::Instance(_) => "an instance".to_string(),
+            _ => format!("a {}", value.type_name()),
+        }
+    ))
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        Ok(value.clone())
+    }
+}
+
+/// Numbers of every type are accepted and converted.
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        value.to_f64().ok_or_else(|| mismatch("a number", value))
+    }
+}
+
+/// Big integers are accepted if they fit.
+impl FromValue for i64 {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        match value {
+            Value::Int(n) => Ok(*n),
+            Value::BigInt(n) => i64::try_from(n)
+                .map_err(|_| RuntimeError::new(format!("{n} does not fit in a 64-bit integer."))),
+            _ => Err(mismatch("an integer", value)),
+        }
+    }
+}
+
+/// Integers are accepted and converted.
+impl FromValue for BigInt {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        value
+            .to_bigint()
+            .ok_or_else(|| mismatch("a big integer", value))
+    }
+}
+
+/// Integers are accepted and converted; floats are not.
+impl FromValue for Decimal {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        value
+            .to_decimal()
+            .ok_or_else(|| mismatch("a decimal", value))
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        match value {
+            Value::Boolean(b) => Ok(*b),
+            _ => Err(mismatch("a boolean", value)),
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            _ => Err(mismatch("a string", value)),
+        }
+    }
+}
+
+/// `nil` becomes `None`.
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        match value {
+            Value::Nil => Ok(None),
+            _ => T::from_value(value).map(Some),
+        }
+    }
+}
+
+/// Rust types that can be built from the whole argument list of a call. A
+/// tuple takes exactly as many arguments as it has elements; a `Vec` takes
+/// any number, all of the same type.
+///
+/// `Vec` is only supported here, as the variadic argument list. There is no
+/// list [`Value`], so a `Vec` cannot be an element of a tuple or returned
+/// from a native function.
+pub trait FromArgs: Sized {
+    const ARITY: Arity;
+
+    fn from_args(arguments: &[Value]) -> Result<Self, RuntimeError>;
+}
+
+impl<T: FromValue> FromArgs for Vec<T> {
+    const ARITY: Arity = Arity::Variadic;
+
+    fn from_args(arguments: &[Value]) -> Result<Self, RuntimeError> {
+        arguments.iter().map(T::from_value).collect()
+    }
+}
+
+macro_rules! tuple_from_args {
+    ($count:literal; $($name:ident $index:tt),*) => {
+        impl<$($name: FromValue),*> FromArgs for ($($name,)*) {
+            const ARITY: Arity = Arity::Fixed($count);
+
+            fn from_args(arguments: &[Value]) -> Result<Self, RuntimeError> {
+                // Calls from scripts are checked against `ARITY` first, but
+                // `from_args` is public and may be given any slice.
+                if arguments.len() != $count {
+                    return Err(RuntimeError::new(format!(
+                        "Expected {} arguments but got {}.",
+                        $count,
+                        arguments.len()
+                    )));
+                }
+                Ok(($($name::from_value(&arguments[$index])?,)*))
+            }
+        }
+    };
+}
+
+tuple_from_args!(0;);
+tuple_from_args!(1; A 0);
+tuple_from_args!(2; A 0, B 1);
+tuple_from_args!(3; A 0, B 1, C 2);
+tuple_from_args!(4; A 0, B 1, C 2, D 3);
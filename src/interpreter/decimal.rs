@@ -0,0 +1,231 @@
+//! Exact fixed-point decimals for money and other quantities that floats
+//! cannot represent, written with a `d` suffix (`19.99d`) or created with
+//! `decimal(...)`.
+
+use crate::interpreter::bigint::BigInt;
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+/// Fractional digits kept by division when neither operand has more.
+pub const DIVISION_SCALE: u32 = 20;
+
+/// `coefficient / 10^scale`. The scale is the number of fractional digits
+/// shown, so `1.50d` keeps its trailing zero; values compare equal
+/// regardless of scale.
+#[derive(Clone)]
+pub struct Decimal {
+    coefficient: BigInt,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Parses an optionally signed decimal such as `-12.50`, ignoring `_`
+    /// separators. At least one digit is required.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (sign, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let digits = |part: &str| part.chars().all(|c| c.is_ascii_digit() || c == '_');
+        let any_digit = unsigned.chars().any(|c| c.is_ascii_digit());
+        if !any_digit || !digits(whole) || !digits(fraction) {
+            return None;
+        }
+
+        let fraction = fraction.replace('_', "");
+        let coefficient = BigInt::parse(&format!("{sign}0{whole}{fraction}"), 10)?;
+        Some(Decimal {
+            coefficient,
+            scale: fraction.len() as u32,
+        })
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    /// The nearest float.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// The coefficient of `self` expressed with `scale` fractional digits,
+    /// which must be at least `self.scale`.
+    fn rescaled(&self, scale: u32) -> BigInt {
+        &self.coefficient * &BigInt::pow10(scale - self.scale)
+    }
+
+    /// The quotient rounded half to even, with at least [`DIVISION_SCALE`]
+    /// fractional digits and trailing zeros trimmed down to the larger
+    /// operand scale. Panics if `divisor` is zero.
+    pub fn div_rounded(&self, divisor: &Decimal) -> Decimal {
+        let scale = self.scale.max(divisor.scale).max(DIVISION_SCALE);
+        // self / divisor = (c1 * 10^(s2 + scale)) / (c2 * 10^s1) / 10^scale
+        let numerator = &self.coefficient * &BigInt::pow10(divisor.scale + scale);
+        let denominator = &divisor.coefficient * &BigInt::pow10(self.scale);
+        let mut result = Decimal {
+            coefficient: div_half_even(&numerator, &denominator),
+            scale,
+        };
+        let keep = self.scale.max(divisor.scale);
+        let ten = BigInt::from(10);
+        while result.scale > keep {
+            let (shorter, digit) = result.coefficient.div_rem(&ten);
+            if !digit.is_zero() {
+                break;
+            }
+            result.coefficient = shorter;
+            result.scale -= 1;
+        }
+        result
+    }
+
+    /// The value rounded half to even to at most `places` fractional digits.
+    /// Values that already have no more digits than that are unchanged.
+    pub fn round(&self, places: u32) -> Decimal {
+        if places >= self.scale {
+            return self.clone();
+        }
+        Decimal {
+            coefficient: div_half_even(&self.coefficient, &BigInt::pow10(self.scale - places)),
+            scale: places,
+        }
+    }
+
+    /// The quotient rounded towards negative infinity, as a whole number.
+    /// Panics if `divisor` is zero.
+    pub fn div_floor(&self, divisor: &Decimal) -> Decimal {
+        let numerator = &self.coefficient * &BigInt::pow10(divisor.scale);
+        let denominator = &divisor.coefficient * &BigInt::pow10(self.scale);
+        Decimal::from(numerator.div_mod_floor(&denominator).0)
+    }
+
+    /// The remainder of [`Decimal::div_floor`], with the sign of `divisor`.
+    pub fn mod_floor(&self, divisor: &Decimal) -> Decimal {
+        self - &(divisor * &self.div_floor(divisor))
+    }
+}
+
+/// `numerator / denominator` rounded to the nearest integer, ties to even.
+fn div_half_even(numerator: &BigInt, denominator: &BigInt) -> BigInt {
+    let (quotient, remainder) = numerator.div_rem(denominator);
+    let twice = &remainder.abs() * &BigInt::from(2);
+    let round_up = match twice.cmp(&denominator.abs()) {
+        Ordering::Greater => true,
+        Ordering::Equal => quotient.is_odd(),
+        Ordering::Less => false,
+    };
+    if !round_up {
+        return quotient;
+    }
+    let step = if numerator.is_negative() != denominator.is_negative() {
+        -1
+    } else {
+        1
+    };
+    &quotient + &BigInt::from(step)
+}
+
+impl From<BigInt> for Decimal {
+    fn from(coefficient: BigInt) -> Self {
+        Decimal {
+            coefficient,
+            scale: 0,
+        }
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(n: i64) -> Self {
+        Decimal::from(BigInt::from(n))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        self.rescaled(scale).cmp(&other.rescaled(scale))
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: &Decimal) -> Decimal {
+        let scale = self.scale.max(other.scale);
+        Decimal {
+            coefficient: &self.rescaled(scale) + &other.rescaled(scale),
+            scale,
+        }
+    }
+}
+
+impl Sub for &Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: &Decimal) -> Decimal {
+        self + &-other
+    }
+}
+
+impl Mul for &Decimal {
+    type Output = Decimal;
+
+    fn mul(self, other: &Decimal) -> Decimal {
+        Decimal {
+            coefficient: &self.coefficient * &other.coefficient,
+            scale: self.scale + other.scale,
+        }
+    }
+}
+
+impl Neg for &Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal {
+            coefficient: -&self.coefficient,
+            scale: self.scale,
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.coefficient.abs().to_string();
+        let scale = self.scale as usize;
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        let text = if fraction.is_empty() {
+            whole.to_string()
+        } else {
+            format!("{whole}.{fraction}")
+        };
+        f.pad_integral(!self.coefficient.is_negative(), "", &text)
+    }
+}
+
+/// Shows the value in decimal rather than its coefficient and scale.
+impl fmt::Debug for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
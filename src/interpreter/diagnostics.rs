@@ -0,0 +1,153 @@
+use crate::interpreter::span::Span;
+use std::fmt::Write;
+
+pub const SCAN_ERROR: &str = "E0001";
+pub const PARSE_ERROR: &str = "E0002";
+pub const RESOLVE_ERROR: &str = "E0003";
+pub const RUNTIME_ERROR: &str = "E0004";
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// A single error report, usually tied to a region of source text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub code: &'static str,
+    pub message: String,
+    /// `None` for runtime errors with no place in the source, such as a bad
+    /// call made from Rust. These render without a location or snippet.
+    pub span: Option<Span>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+    /// Set when the error was caused by the input ending too early, such as
+    /// an unterminated string or a missing closing brace. The REPL uses this
+    /// to keep reading instead of reporting the error.
+    pub unexpected_eof: bool,
+}
+
+impl Diagnostic {
+    pub fn error(
+        code: &'static str,
+        message: impl Into<String>,
+        span: impl Into<Option<Span>>,
+    ) -> Self {
+        Diagnostic {
+            code,
+            message: message.into(),
+            span: span.into(),
+            notes: Vec::new(),
+            help: None,
+            unexpected_eof: false,
+        }
+    }
+
+    pub fn at_eof(mut self) -> Self {
+        self.unexpected_eof = true;
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Renders the diagnostic with the offending source line and a caret
+    /// underline beneath the span, e.g.
+    ///
+    /// ```text
+    /// error[E0002]: Expect expression.
+    ///  --> 1:11
+    ///   |
+    /// 1 | print 1 + ;
+    ///   |           ^
+    /// ```
+    ///
+    /// `first_line` is the line number `source` starts at, which lets the
+    /// REPL render a single input against its position in the session.
+    pub fn render(&self, source: &str, first_line: usize, color: bool) -> String {
+        let paint = |style: &str, text: &str| {
+            if color {
+                format!("{style}{text}{RESET}")
+            } else {
+                text.to_string()
+            }
+        };
+
+        let gutter = self.span.map_or(String::new(), |span| {
+            " ".repeat(span.line.to_string().len())
+        });
+        let bar = paint(BLUE, "|");
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "{}{} {}",
+            paint(RED, &format!("error[{}]", self.code)),
+            paint(BOLD, ":"),
+            paint(BOLD, &self.message)
+        );
+        if let Some(span) = self.span {
+            let _ = writeln!(
+                out,
+                "{gutter}{} {}:{}",
+                paint(BLUE, "-->"),
+                span.line,
+                span.column
+            );
+
+            // `split` rather than `lines` so that an EOF span sitting after a
+            // trailing newline still has an (empty) line to point at. A span
+            // outside `source` gets no snippet rather than a wrong one.
+            let line = span
+                .line
+                .checked_sub(first_line)
+                .and_then(|index| source.split('\n').nth(index))
+                .map(|line| line.strip_suffix('\r').unwrap_or(line));
+            if let Some(line) = line {
+                let line_number = paint(BLUE, &span.line.to_string());
+                // Tabs are kept so the carets line up however wide the
+                // terminal shows them.
+                let padding: String = line
+                    .chars()
+                    .take(span.column.saturating_sub(1))
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                let carets = "^".repeat(underline_width(span, source, line));
+
+                let _ = writeln!(out, "{gutter} {bar}");
+                let _ = writeln!(out, "{}", format!("{line_number} {bar} {line}").trim_end());
+                let _ = writeln!(out, "{gutter} {bar} {padding}{}", paint(RED, &carets));
+            }
+        }
+
+        for note in &self.notes {
+            let _ = writeln!(out, "{gutter} {} note: {note}", paint(BLUE, "="));
+        }
+        if let Some(help) = &self.help {
+            let _ = writeln!(out, "{gutter} {} help: {help}", paint(BLUE, "="));
+        }
+
+        out
+    }
+}
+
+/// Number of characters to underline on the span's first line. Spans that
+/// continue onto later lines are underlined to the end of the line.
+fn underline_width(span: Span, source: &str, line: &str) -> usize {
+    let available = line
+        .chars()
+        .count()
+        .saturating_sub(span.column.saturating_sub(1));
+    let span_len = source
+        .get(span.start..span.end)
+        .map_or(0, |text| text.chars().take_while(|c| *c != '\n').count());
+
+    span_len.min(available).max(1)
+}
@@ -0,0 +1,114 @@
+use crate::interpreter::{
+    interpreter::{RuntimeError, Value},
+    scanner::Token,
+};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+#[derive(Debug, Default)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+    enclosing: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Environment {
+            values: HashMap::new(),
+            enclosing: None,
+        }
+    }
+
+    pub fn with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Self {
+        Environment {
+            values: HashMap::new(),
+            enclosing: Some(enclosing),
+        }
+    }
+
+    pub fn define(&mut self, name: &str, value: Value) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Value, RuntimeError> {
+        let key = name.lexeme.as_str();
+        self.lookup(key).ok_or_else(|| RuntimeError {
+            span: Some(name.span),
+            message: format!("Undefined variable '{key}'."),
+        })
+    }
+
+    /// The names and values defined directly in this scope, sorted by name.
+    pub fn bindings(&self) -> Vec<(String, Value)> {
+        let mut bindings: Vec<(String, Value)> = self
+            .values
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        bindings
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<Value> {
+        if let Some(value) = self.values.get(name) {
+            return Some(value.clone());
+        }
+
+        self.enclosing
+            .as_ref()
+            .and_then(|enclosing| enclosing.borrow().lookup(name))
+    }
+
+    fn ancestor(
+        environment: &Rc<RefCell<Environment>>,
+        distance: usize,
+    ) -> Rc<RefCell<Environment>> {
+        let mut environment = Rc::clone(environment);
+        for _ in 0..distance {
+            let enclosing = environment
+                .borrow()
+                .enclosing
+                .clone()
+                .expect("resolver depth exceeds environment chain");
+            environment = enclosing;
+        }
+        environment
+    }
+
+    /// Reads `name` from the environment exactly `distance` scopes up the
+    /// chain, as computed by the resolver.
+    pub fn get_at(environment: &Rc<RefCell<Environment>>, distance: usize, name: &str) -> Value {
+        Self::ancestor(environment, distance)
+            .borrow()
+            .values
+            .get(name)
+            .cloned()
+            .unwrap_or(Value::Nil)
+    }
+
+    pub fn assign_at(
+        environment: &Rc<RefCell<Environment>>,
+        distance: usize,
+        name: &Token,
+        value: Value,
+    ) {
+        Self::ancestor(environment, distance)
+            .borrow_mut()
+            .define(&name.lexeme, value);
+    }
+
+    pub fn assign(&mut self, name: &Token, value: Value) -> Result<(), RuntimeError> {
+        let key = name.lexeme.as_str();
+        if let Some(slot) = self.values.get_mut(key) {
+            *slot = value;
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(RuntimeError {
+                span: Some(name.span),
+                message: format!("Cannot assign to undeclared variable '{key}'."),
+            }),
+        }
+    }
+}
@@ -1,11 +1,22 @@
 use crate::interpreter::scanner::Literal;
 use crate::interpreter::scanner::Token;
+use crate::interpreter::span::Span;
+use std::cell::Cell;
 
 pub trait Visitor<T> {
     fn visit_binary_expr(&mut self, expr: &BinaryExpr) -> T;
     fn visit_unary_expr(&mut self, expr: &UnaryExpr) -> T;
     fn visit_grouping_expr(&mut self, expr: &GroupingExpr) -> T;
     fn visit_literal_expr(&mut self, expr: &LiteralExpr) -> T;
+    fn visit_variable_expr(&mut self, expr: &VariableExpr) -> T;
+    fn visit_assign_expr(&mut self, expr: &AssignExpr) -> T;
+    fn visit_logical_expr(&mut self, expr: &LogicalExpr) -> T;
+    fn visit_call_expr(&mut self, expr: &CallExpr) -> T;
+    fn visit_get_expr(&mut self, expr: &GetExpr) -> T;
+    fn visit_set_expr(&mut self, expr: &SetExpr) -> T;
+    fn visit_this_expr(&mut self, expr: &ThisExpr) -> T;
+    fn visit_super_expr(&mut self, expr: &SuperExpr) -> T;
+    fn visit_interpolation_expr(&mut self, expr: &InterpolationExpr) -> T;
 }
 
 #[derive(Debug, Clone)]
@@ -14,6 +25,15 @@ pub enum Expr {
     Unary(Box<UnaryExpr>),
     Grouping(Box<GroupingExpr>),
     Literal(LiteralExpr),
+    Variable(VariableExpr),
+    Assign(Box<AssignExpr>),
+    Logical(Box<LogicalExpr>),
+    Call(Box<CallExpr>),
+    Get(Box<GetExpr>),
+    Set(Box<SetExpr>),
+    This(ThisExpr),
+    Super(SuperExpr),
+    Interpolation(InterpolationExpr),
 }
 
 impl Expr {
@@ -23,6 +43,34 @@ impl Expr {
             Expr::Unary(expr) => visitor.visit_unary_expr(expr),
             Expr::Grouping(expr) => visitor.visit_grouping_expr(expr),
             Expr::Literal(expr) => visitor.visit_literal_expr(expr),
+            Expr::Variable(expr) => visitor.visit_variable_expr(expr),
+            Expr::Assign(expr) => visitor.visit_assign_expr(expr),
+            Expr::Logical(expr) => visitor.visit_logical_expr(expr),
+            Expr::Call(expr) => visitor.visit_call_expr(expr),
+            Expr::Get(expr) => visitor.visit_get_expr(expr),
+            Expr::Set(expr) => visitor.visit_set_expr(expr),
+            Expr::This(expr) => visitor.visit_this_expr(expr),
+            Expr::Super(expr) => visitor.visit_super_expr(expr),
+            Expr::Interpolation(expr) => visitor.visit_interpolation_expr(expr),
+        }
+    }
+
+    /// The region of source this expression was parsed from.
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Binary(expr) => expr.left.span().to(expr.right.span()),
+            Expr::Unary(expr) => expr.operator.span.to(expr.right.span()),
+            Expr::Grouping(expr) => expr.span,
+            Expr::Literal(expr) => expr.span,
+            Expr::Variable(expr) => expr.name.span,
+            Expr::Assign(expr) => expr.name.span.to(expr.value.span()),
+            Expr::Logical(expr) => expr.left.span().to(expr.right.span()),
+            Expr::Call(expr) => expr.callee.span().to(expr.paren.span),
+            Expr::Get(expr) => expr.object.span().to(expr.name.span),
+            Expr::Set(expr) => expr.object.span().to(expr.value.span()),
+            Expr::This(expr) => expr.keyword.span,
+            Expr::Super(expr) => expr.keyword.span.to(expr.method.span),
+            Expr::Interpolation(expr) => expr.span,
         }
     }
 }
@@ -43,9 +91,75 @@ pub struct UnaryExpr {
 #[derive(Debug, Clone)]
 pub struct GroupingExpr {
     pub expression: Expr,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct LiteralExpr {
     pub value: Literal,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariableExpr {
+    pub name: Token,
+    /// Number of scopes between this reference and its declaration, filled
+    /// in by the resolver. `None` means the name is looked up as a global.
+    pub depth: Cell<Option<usize>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AssignExpr {
+    pub name: Token,
+    pub value: Expr,
+    pub depth: Cell<Option<usize>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogicalExpr {
+    pub left: Expr,
+    pub operator: Token,
+    pub right: Expr,
+}
+
+#[derive(Debug, Clone)]
+pub struct CallExpr {
+    pub callee: Expr,
+    pub paren: Token,
+    pub arguments: Vec<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetExpr {
+    pub object: Expr,
+    pub name: Token,
+}
+
+#[derive(Debug, Clone)]
+pub struct SetExpr {
+    pub object: Expr,
+    pub name: Token,
+    pub value: Expr,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThisExpr {
+    pub keyword: Token,
+    pub depth: Cell<Option<usize>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SuperExpr {
+    pub keyword: Token,
+    pub method: Token,
+    pub depth: Cell<Option<usize>>,
+}
+
+/// A string with `${...}` interpolations, such as `"Hi ${name}!"`. `parts`
+/// alternates between the literal text and the interpolated expressions,
+/// with empty text segments left out.
+#[derive(Debug, Clone)]
+pub struct InterpolationExpr {
+    pub parts: Vec<Expr>,
+    pub span: Span,
 }
@@ -1,89 +1,403 @@
 use crate::interpreter::{
+    bigint::BigInt,
+    callable::{Arity, Callable, KestrlFunction, NativeFunction},
+    class::{KestrlClass, KestrlInstance},
+    convert::FromArgs,
+    decimal::Decimal,
+    diagnostics::{Diagnostic, RUNTIME_ERROR},
+    environment::Environment,
     expressions::{self, Visitor},
-    scanner::Literal,
+    scanner::{Literal, Token},
+    span::Span,
+    statements::{self, Stmt},
     token_types::TokenType,
 };
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    io::{self, Write},
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub struct Interpreter {
+    pub globals: Rc<RefCell<Environment>>,
+    environment: Rc<RefCell<Environment>>,
+    /// When set, the value of each top-level expression statement is printed
+    /// the way the REPL echoes results. `nil` results are not echoed.
+    pub echo: bool,
+    /// Where `print` and echoed values are written. Defaults to stdout.
+    output: Box<dyn Write>,
+    /// Number of calls currently in progress, see [`MAX_CALL_DEPTH`].
+    call_depth: usize,
+}
 
-pub struct Interpreter;
+/// How deeply calls may nest before a script is stopped with a stack
+/// overflow error. Each call takes several native frames, so this is sized
+/// for a debug build running on a [`crate::kestrl::STACK_SIZE`] stack.
+pub const MAX_CALL_DEPTH: usize = 2500;
 
+#[derive(Debug)]
 pub struct RuntimeError {
+    /// Where in the script the error happened. `None` for errors that have
+    /// no place in the source, such as a bad call made from Rust.
+    pub span: Option<Span>,
     pub message: String,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl RuntimeError {
+    /// An error without a source position, for native functions to return.
+    /// The interpreter reports it at the call site.
+    pub fn new(message: impl Into<String>) -> Self {
+        RuntimeError {
+            span: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "{}:{}: {}", span.line, span.column, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+impl From<RuntimeError> for Diagnostic {
+    fn from(error: RuntimeError) -> Self {
+        Diagnostic::error(RUNTIME_ERROR, error.message, error.span)
+    }
+}
+
+/// Non-local exits raised while executing statements. `return` unwinds to
+/// the enclosing function call the same way a runtime error unwinds to
+/// `interpret`.
+#[derive(Debug)]
+pub enum Unwind {
+    Error(RuntimeError),
+    Return(Value),
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum Value {
     Nil,
     Boolean(bool),
+    Int(i64),
+    BigInt(BigInt),
+    Decimal(Decimal),
     Number(f64),
     String(String),
+    Function(Rc<dyn Callable>),
+    Class(Rc<KestrlClass>),
+    Instance(Rc<RefCell<KestrlInstance>>),
+}
+
+/// Values are only equal to values of the same type, so `Value::Int(1)` is
+/// not equal to `Value::Number(1.0)`. Kestrl's `==` compares numbers of
+/// different types by value, see [`Interpreter::is_equal`].
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::Decimal(a), Value::Decimal(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
+            (Value::Class(a), Value::Class(b)) => Rc::ptr_eq(a, b),
+            (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Value {
+    /// The name of the value's type, as used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "nil",
+            Value::Boolean(_) => "boolean",
+            Value::Int(_) => "integer",
+            Value::BigInt(_) => "big integer",
+            Value::Decimal(_) => "decimal",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Function(_) => "function",
+            Value::Class(_) => "class",
+            Value::Instance(_) => "instance",
+        }
+    }
+
+    /// How the REPL echoes a value: like `Display`, except that strings are
+    /// shown quoted and escaped, whole floats keep their `.0`, and big
+    /// integers and decimals keep their suffix, so they can be told apart
+    /// from other values.
+    pub fn repr(&self) -> String {
+        match self {
+            Value::String(s) => format!("{s:?}"),
+            Value::BigInt(n) => format!("{n}n"),
+            Value::Decimal(d) => format!("{d}d"),
+            Value::Number(n) if n.fract() == 0.0 => format!("{n:?}"),
+            _ => self.to_string(),
+        }
+    }
+
+    /// The value as a float, if it is a number of any type.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(n) => Some(*n as f64),
+            Value::BigInt(n) => Some(n.to_f64()),
+            Value::Decimal(d) => Some(d.to_f64()),
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The value as a big integer, if it is an integer of either size.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Value::Int(n) => Some(BigInt::from(*n)),
+            Value::BigInt(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+
+    /// The value as a decimal, if it is an integer or a decimal. Floats are
+    /// only converted explicitly, with `decimal(...)`.
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            Value::Decimal(d) => Some(d.clone()),
+            _ => self.to_bigint().map(Decimal::from),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Nil => write!(f, "nil"),
+            Value::Boolean(b) => write!(f, "{b}"),
+            Value::Int(n) => write!(f, "{n}"),
+            Value::BigInt(n) => write!(f, "{n}"),
+            Value::Decimal(d) => write!(f, "{d}"),
+            Value::Number(n) => format_number(*n, f),
+            Value::String(s) => write!(f, "{s}"),
+            Value::Function(function) => write!(f, "<fn {}>", function.name()),
+            Value::Class(class) => write!(f, "{}", class.name),
+            Value::Instance(instance) => write!(f, "{} instance", instance.borrow().class.name),
+        }
+    }
+}
+
+/// Whole numbers print without a fractional part, so `8 * 5` shows `40`
+/// rather than `40.0`.
+fn format_number(n: f64, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if n.is_nan() {
+        write!(f, "nan")
+    } else if n.is_infinite() {
+        write!(f, "{}inf", if n < 0.0 { "-" } else { "" })
+    } else if n == 0.0 {
+        // Covers -0.0 as well.
+        write!(f, "0")
+    } else {
+        write!(f, "{n}")
+    }
+}
+
+/// The operands of an arithmetic or comparison operator, brought to a
+/// common type. Exact types widen from integer to big integer to decimal,
+/// and anything mixed with a float is converted to a float.
+enum Operands {
+    Int(i64, i64),
+    Big(BigInt, BigInt),
+    Decimal(Decimal, Decimal),
+    Float(f64, f64),
+}
+
+impl Operands {
+    fn of(left: &Value, right: &Value) -> Option<Operands> {
+        match (left, right) {
+            (Value::Int(l), Value::Int(r)) => Some(Operands::Int(*l, *r)),
+            (Value::Number(_), _) | (_, Value::Number(_)) => {
+                Some(Operands::Float(left.to_f64()?, right.to_f64()?))
+            }
+            (Value::Decimal(_), _) | (_, Value::Decimal(_)) => {
+                Some(Operands::Decimal(left.to_decimal()?, right.to_decimal()?))
+            }
+            _ => Some(Operands::Big(left.to_bigint()?, right.to_bigint()?)),
+        }
+    }
+
+    fn divisor_is_zero(&self) -> bool {
+        match self {
+            Operands::Int(_, r) => *r == 0,
+            Operands::Big(_, r) => r.is_zero(),
+            Operands::Decimal(_, r) => r.is_zero(),
+            Operands::Float(_, r) => *r == 0.0,
+        }
+    }
+
+    /// `None` if either float is NaN.
+    fn compare(&self) -> Option<Ordering> {
+        match self {
+            Operands::Int(l, r) => Some(l.cmp(r)),
+            Operands::Big(l, r) => Some(l.cmp(r)),
+            Operands::Decimal(l, r) => Some(l.cmp(r)),
+            Operands::Float(l, r) => l.partial_cmp(r),
+        }
+    }
+}
+
+/// Integer division rounding towards negative infinity. `None` on overflow.
+fn floor_div(l: i64, r: i64) -> Option<i64> {
+    let q = l.checked_div(r)?;
+    if l % r != 0 && (l < 0) != (r < 0) {
+        Some(q - 1)
+    } else {
+        Some(q)
+    }
+}
+
+fn int_result(result: Option<i64>, operator: &Token) -> Result<Value, RuntimeError> {
+    result.map(Value::Int).ok_or_else(|| RuntimeError {
+        span: Some(operator.span),
+        message: "Integer overflow.".to_string(),
+    })
+}
+
+fn numbers_expected(operator: &Token) -> RuntimeError {
+    RuntimeError {
+        span: Some(operator.span),
+        message: "Operands must be numbers.".to_string(),
+    }
+}
+
+fn division_by_zero(operator: &Token) -> RuntimeError {
+    RuntimeError {
+        span: Some(operator.span),
+        message: "Division by zero.".to_string(),
+    }
 }
 
 impl Visitor<Result<Value, RuntimeError>> for Interpreter {
     fn visit_binary_expr(&mut self, expr: &expressions::BinaryExpr) -> Result<Value, RuntimeError> {
         let left = self.evaluate(&expr.left)?;
         let right = self.evaluate(&expr.right)?;
+        let operator = &expr.operator;
 
-        match expr.operator.token_type {
+        match operator.token_type {
             TokenType::PLUS => match (left, right) {
-                (Value::Number(l), Value::Number(r)) => Ok(Value::Number(l + r)),
                 (Value::String(l), Value::String(r)) => Ok(Value::String(l + &r)),
-                _ => Err(RuntimeError {
-                    message: "Operands must be two numbers or two strings.".to_string(),
-                }),
+                (left, right) => match Operands::of(&left, &right) {
+                    Some(Operands::Int(l, r)) => int_result(l.checked_add(r), operator),
+                    Some(Operands::Big(l, r)) => Ok(Value::BigInt(&l + &r)),
+                    Some(Operands::Decimal(l, r)) => Ok(Value::Decimal(&l + &r)),
+                    Some(Operands::Float(l, r)) => Ok(Value::Number(l + r)),
+                    None => Err(RuntimeError {
+                        span: Some(operator.span),
+                        message: "Operands must be two numbers or two strings.".to_string(),
+                    }),
+                },
             },
-            TokenType::MINUS => match (left, right) {
-                (Value::Number(l), Value::Number(r)) => Ok(Value::Number(l - r)),
-                _ => Err(RuntimeError {
-                    message: "Operands must be numbers.".to_string(),
-                }),
+            TokenType::MINUS => match Operands::of(&left, &right) {
+                Some(Operands::Int(l, r)) => int_result(l.checked_sub(r), operator),
+                Some(Operands::Big(l, r)) => Ok(Value::BigInt(&l - &r)),
+                Some(Operands::Decimal(l, r)) => Ok(Value::Decimal(&l - &r)),
+                Some(Operands::Float(l, r)) => Ok(Value::Number(l - r)),
+                None => Err(numbers_expected(operator)),
             },
-            TokenType::STAR => match (left, right) {
-                (Value::Number(l), Value::Number(r)) => Ok(Value::Number(l * r)),
-                _ => Err(RuntimeError {
-                    message: "Operands must be numbers.".to_string(),
-                }),
+            TokenType::STAR => match Operands::of(&left, &right) {
+                Some(Operands::Int(l, r)) => int_result(l.checked_mul(r), operator),
+                Some(Operands::Big(l, r)) => Ok(Value::BigInt(&l * &r)),
+                Some(Operands::Decimal(l, r)) => Ok(Value::Decimal(&l * &r)),
+                Some(Operands::Float(l, r)) => Ok(Value::Number(l * r)),
+                None => Err(numbers_expected(operator)),
+            },
+            // `/` never truncates, so `7 / 2` is `3.5`; `~/` floors. Big
+            // integers divide to a decimal to stay exact.
+            TokenType::SLASH => match Operands::of(&left, &right) {
+                Some(operands) if operands.divisor_is_zero() => Err(division_by_zero(operator)),
+                Some(Operands::Int(l, r)) => Ok(Value::Number(l as f64 / r as f64)),
+                Some(Operands::Big(l, r)) => Ok(Value::Decimal(
+                    Decimal::from(l).div_rounded(&Decimal::from(r)),
+                )),
+                Some(Operands::Decimal(l, r)) => Ok(Value::Decimal(l.div_rounded(&r))),
+                Some(Operands::Float(l, r)) => Ok(Value::Number(l / r)),
+                None => Err(numbers_expected(operator)),
             },
-            TokenType::SLASH => match (left, right) {
-                (Value::Number(l), Value::Number(r)) => {
-                    if r == 0.0 {
-                        Err(RuntimeError {
-                            message: "Division by zero.".to_string(),
-                        })
+            TokenType::TILDE_SLASH => match Operands::of(&left, &right) {
+                Some(operands) if operands.divisor_is_zero() => Err(division_by_zero(operator)),
+                Some(Operands::Int(l, r)) => int_result(floor_div(l, r), operator),
+                Some(Operands::Big(l, r)) => Ok(Value::BigInt(l.div_mod_floor(&r).0)),
+                Some(Operands::Decimal(l, r)) => Ok(Value::Decimal(l.div_floor(&r))),
+                Some(Operands::Float(l, r)) => Ok(Value::Number((l / r).floor())),
+                None => Err(numbers_expected(operator)),
+            },
+            // The result takes the sign of the divisor, matching `~/`.
+            TokenType::PERCENT => match Operands::of(&left, &right) {
+                Some(operands) if operands.divisor_is_zero() => Err(division_by_zero(operator)),
+                Some(Operands::Int(l, r)) => {
+                    let m = l.wrapping_rem(r);
+                    Ok(Value::Int(if m != 0 && (m < 0) != (r < 0) {
+                        m + r
                     } else {
-                        Ok(Value::Number(l / r))
-                    }
+                        m
+                    }))
                 }
-                _ => Err(RuntimeError {
-                    message: "Operands must be numbers.".to_string(),
-                }),
+                Some(Operands::Big(l, r)) => Ok(Value::BigInt(l.div_mod_floor(&r).1)),
+                Some(Operands::Decimal(l, r)) => Ok(Value::Decimal(l.mod_floor(&r))),
+                Some(Operands::Float(l, r)) => {
+                    let m = l % r;
+                    Ok(Value::Number(if m != 0.0 && (m < 0.0) != (r < 0.0) {
+                        m + r
+                    } else {
+                        m
+                    }))
+                }
+                None => Err(numbers_expected(operator)),
             },
-            TokenType::GREATER => match (left, right) {
-                (Value::Number(l), Value::Number(r)) => Ok(Value::Boolean(l > r)),
-                _ => Err(RuntimeError {
-                    message: "Operands must be numbers.".to_string(),
-                }),
+            TokenType::GREATER => match Operands::of(&left, &right) {
+                Some(operands) => Ok(Value::Boolean(
+                    operands.compare().is_some_and(Ordering::is_gt),
+                )),
+                None => Err(numbers_expected(operator)),
             },
-            TokenType::GREATER_EQUAL => match (left, right) {
-                (Value::Number(l), Value::Number(r)) => Ok(Value::Boolean(l >= r)),
-                _ => Err(RuntimeError {
-                    message: "Operands must be numbers.".to_string(),
-                }),
+            TokenType::GREATER_EQUAL => match Operands::of(&left, &right) {
+                Some(operands) => Ok(Value::Boolean(
+                    operands.compare().is_some_and(Ordering::is_ge),
+                )),
+                None => Err(numbers_expected(operator)),
             },
-            TokenType::LESS => match (left, right) {
-                (Value::Number(l), Value::Number(r)) => Ok(Value::Boolean(l < r)),
-                _ => Err(RuntimeError {
-                    message: "Operands must be numbers.".to_string(),
-                }),
+            TokenType::LESS => match Operands::of(&left, &right) {
+                Some(operands) => Ok(Value::Boolean(
+                    operands.compare().is_some_and(Ordering::is_lt),
+                )),
+                None => Err(numbers_expected(operator)),
             },
-            TokenType::LESS_EQUAL => match (left, right) {
-                (Value::Number(l), Value::Number(r)) => Ok(Value::Boolean(l <= r)),
-                _ => Err(RuntimeError {
-                    message: "Operands must be numbers.".to_string(),
-                }),
+            TokenType::LESS_EQUAL => match Operands::of(&left, &right) {
+                Some(operands) => Ok(Value::Boolean(
+                    operands.compare().is_some_and(Ordering::is_le),
+                )),
+                None => Err(numbers_expected(operator)),
             },
             TokenType::EQUAL_EQUAL => Ok(Value::Boolean(self.is_equal(&left, &right))),
             TokenType::BANG_EQUAL => Ok(Value::Boolean(!self.is_equal(&left, &right))),
             _ => Err(RuntimeError {
+                span: Some(operator.span),
                 message: "Unknown binary operator.".to_string(),
             }),
         }
@@ -94,13 +408,18 @@ impl Visitor<Result<Value, RuntimeError>> for Interpreter {
 
         match expr.operator.token_type {
             TokenType::MINUS => match right {
+                Value::Int(r) => int_result(r.checked_neg(), &expr.operator),
+                Value::BigInt(r) => Ok(Value::BigInt(-&r)),
+                Value::Decimal(r) => Ok(Value::Decimal(-&r)),
                 Value::Number(r) => Ok(Value::Number(-r)),
                 _ => Err(RuntimeError {
+                    span: Some(expr.operator.span),
                     message: "Operand must be a number.".to_string(),
                 }),
             },
-            TokenType::BANG => Ok(Value::Boolean(self.is_truthy(&right))),
+            TokenType::BANG => Ok(Value::Boolean(!self.is_truthy(&right))),
             _ => Err(RuntimeError {
+                span: Some(expr.operator.span),
                 message: "Unknown unary operator.".to_string(),
             }),
         }
@@ -120,24 +439,445 @@ impl Visitor<Result<Value, RuntimeError>> for Interpreter {
         match &expr.value {
             Literal::Nil => Ok(Value::Nil),
             Literal::Bool(b) => Ok(Value::Boolean(*b)),
+            Literal::Int(n) => Ok(Value::Int(*n)),
+            Literal::BigInt(n) => Ok(Value::BigInt((**n).clone())),
+            Literal::Decimal(d) => Ok(Value::Decimal((**d).clone())),
             Literal::Number(n) => Ok(Value::Number(*n)),
             Literal::Str(s) => Ok(Value::String(s.clone())),
             Literal::Unknown(u) => Err(RuntimeError {
+                span: Some(expr.span),
                 message: format!("Unknown literal: {u}."),
             }),
         }
     }
+
+    fn visit_variable_expr(
+        &mut self,
+        expr: &expressions::VariableExpr,
+    ) -> Result<Value, RuntimeError> {
+        self.look_up_variable(&expr.name, expr.depth.get())
+    }
+
+    fn visit_assign_expr(&mut self, expr: &expressions::AssignExpr) -> Result<Value, RuntimeError> {
+        let value = self.evaluate(&expr.value)?;
+        match expr.depth.get() {
+            Some(distance) => {
+                Environment::assign_at(&self.environment, distance, &expr.name, value.clone())
+            }
+            None => self
+                .globals
+                .borrow_mut()
+                .assign(&expr.name, value.clone())?,
+        }
+        Ok(value)
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        expr: &expressions::LogicalExpr,
+    ) -> Result<Value, RuntimeError> {
+        let left = self.evaluate(&expr.left)?;
+
+        if expr.operator.token_type == TokenType::OR {
+            if self.is_truthy(&left) {
+                return Ok(left);
+            }
+        } else if !self.is_truthy(&left) {
+            return Ok(left);
+        }
+
+        self.evaluate(&expr.right)
+    }
+
+    fn visit_call_expr(&mut self, expr: &expressions::CallExpr) -> Result<Value, RuntimeError> {
+        let callee = self.evaluate(&expr.callee)?;
+
+        let mut arguments = Vec::with_capacity(expr.arguments.len());
+        for argument in &expr.arguments {
+            arguments.push(self.evaluate(argument)?);
+        }
+
+        let result = self.call_value(callee, arguments, Some(expr.paren.span));
+        // Native functions have no source position of their own.
+        result.map_err(|mut error| {
+            error
+                .span
+                .get_or_insert_with(|| expr.callee.span().to(expr.paren.span));
+            error
+        })
+    }
+
+    fn visit_get_expr(&mut self, expr: &expressions::GetExpr) -> Result<Value, RuntimeError> {
+        match self.evaluate(&expr.object)? {
+            Value::Instance(instance) => KestrlInstance::get(&instance, &expr.name),
+            _ => Err(RuntimeError {
+                span: Some(expr.name.span),
+                message: "Only instances have properties.".to_string(),
+            }),
+        }
+    }
+
+    fn visit_set_expr(&mut self, expr: &expressions::SetExpr) -> Result<Value, RuntimeError> {
+        let Value::Instance(instance) = self.evaluate(&expr.object)? else {
+            return Err(RuntimeError {
+                span: Some(expr.name.span),
+                message: "Only instances have fields.".to_string(),
+            });
+        };
+
+        let value = self.evaluate(&expr.value)?;
+        instance.borrow_mut().set(&expr.name, value.clone());
+        Ok(value)
+    }
+
+    fn visit_this_expr(&mut self, expr: &expressions::ThisExpr) -> Result<Value, RuntimeError> {
+        self.look_up_variable(&expr.keyword, expr.depth.get())
+    }
+
+    fn visit_super_expr(&mut self, expr: &expressions::SuperExpr) -> Result<Value, RuntimeError> {
+        // The resolver places the "this" scope directly inside the "super" scope.
+        let distance = expr.depth.get().unwrap_or(0);
+        let superclass = match Environment::get_at(&self.environment, distance, "super") {
+            Value::Class(class) => class,
+            _ => {
+                return Err(RuntimeError {
+                    span: Some(expr.keyword.span),
+                    message: "Superclass must be a class.".to_string(),
+                });
+            }
+        };
+        let instance = Environment::get_at(&self.environment, distance.saturating_sub(1), "this");
+
+        let name = expr.method.lexeme.clone();
+        match superclass.find_method(&name) {
+            Some(method) => Ok(Value::Function(Rc::new(method.bind(instance)))),
+            None => Err(RuntimeError {
+                span: Some(expr.method.span),
+                message: format!("Undefined property '{name}'."),
+            }),
+        }
+    }
+
+    fn visit_interpolation_expr(
+        &mut self,
+        expr: &expressions::InterpolationExpr,
+    ) -> Result<Value, RuntimeError> {
+        let mut text = String::new();
+        for part in &expr.parts {
+            text.push_str(&self.evaluate(part)?.to_string());
+        }
+        Ok(Value::String(text))
+    }
+}
+
+impl statements::Visitor<Result<(), Unwind>> for Interpreter {
+    fn visit_expression_stmt(&mut self, stmt: &statements::ExpressionStmt) -> Result<(), Unwind> {
+        self.evaluate(&stmt.expression)?;
+        Ok(())
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &statements::PrintStmt) -> Result<(), Unwind> {
+        let value = self.evaluate(&stmt.expression)?;
+        self.write_line(&value.to_string(), stmt.span)?;
+        Ok(())
+    }
+
+    fn visit_var_stmt(&mut self, stmt: &statements::VarStmt) -> Result<(), Unwind> {
+        let value = match &stmt.initializer {
+            Some(initializer) => self.evaluate(initializer)?,
+            None => Value::Nil,
+        };
+        self.environment
+            .borrow_mut()
+            .define(&stmt.name.lexeme, value);
+        Ok(())
+    }
+
+    fn visit_block_stmt(&mut self, stmt: &statements::BlockStmt) -> Result<(), Unwind> {
+        let environment = Environment::with_enclosing(Rc::clone(&self.environment));
+        self.execute_block(&stmt.statements, Rc::new(RefCell::new(environment)))
+    }
+
+    fn visit_if_stmt(&mut self, stmt: &statements::IfStmt) -> Result<(), Unwind> {
+        let condition = self.evaluate(&stmt.condition)?;
+        if self.is_truthy(&condition) {
+            self.execute(&stmt.then_branch)?;
+        } else if let Some(else_branch) = &stmt.else_branch {
+            self.execute(else_branch)?;
+        }
+        Ok(())
+    }
+
+    fn visit_while_stmt(&mut self, stmt: &statements::WhileStmt) -> Result<(), Unwind> {
+        loop {
+            let condition = self.evaluate(&stmt.condition)?;
+            if !self.is_truthy(&condition) {
+                break;
+            }
+            self.execute(&stmt.body)?;
+        }
+        Ok(())
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Rc<statements::FunctionStmt>) -> Result<(), Unwind> {
+        let function = KestrlFunction::new(Rc::clone(stmt), Rc::clone(&self.environment), false);
+        self.environment
+            .borrow_mut()
+            .define(&stmt.name.lexeme, Value::Function(Rc::new(function)));
+        Ok(())
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &statements::ReturnStmt) -> Result<(), Unwind> {
+        let value = match &stmt.value {
+            Some(value) => self.evaluate(value)?,
+            None => Value::Nil,
+        };
+        Err(Unwind::Return(value))
+    }
+
+    fn visit_class_stmt(&mut self, stmt: &statements::ClassStmt) -> Result<(), Unwind> {
+        let superclass = match &stmt.superclass {
+            Some(superclass) => match self.evaluate(superclass)? {
+                Value::Class(class) => Some(class),
+                _ => {
+                    return Err(Unwind::Error(RuntimeError {
+                        span: Some(superclass.span()),
+                        message: "Superclass must be a class.".to_string(),
+                    }));
+                }
+            },
+            None => None,
+        };
+
+        let name = stmt.name.lexeme.clone();
+        self.environment.borrow_mut().define(&name, Value::Nil);
+
+        let previous = Rc::clone(&self.environment);
+        if let Some(superclass) = &superclass {
+            let mut environment = Environment::with_enclosing(Rc::clone(&self.environment));
+            environment.define("super", Value::Class(Rc::clone(superclass)));
+            self.environment = Rc::new(RefCell::new(environment));
+        }
+
+        let mut methods = HashMap::new();
+        for method in &stmt.methods {
+            let method_name = method.name.lexeme.clone();
+            let function = KestrlFunction::new(
+                Rc::clone(method),
+                Rc::clone(&self.environment),
+                method_name == "init",
+            );
+            methods.insert(method_name, Rc::new(function));
+        }
+
+        self.environment = previous;
+
+        let class = KestrlClass::new(name, superclass, methods);
+        self.environment
+            .borrow_mut()
+            .assign(&stmt.name, Value::Class(Rc::new(class)))?;
+        Ok(())
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        Interpreter
+        let globals = Rc::new(RefCell::new(Environment::new()));
+        let mut interpreter = Interpreter {
+            environment: Rc::clone(&globals),
+            globals,
+            echo: false,
+            output: Box::new(io::stdout()),
+            call_depth: 0,
+        };
+        interpreter.define_builtins();
+        interpreter
+    }
+
+    fn define_builtins(&mut self) {
+        self.define_fn("clock", |()| {
+            let elapsed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|_| RuntimeError::new("The system clock is set before 1970."))?;
+            Ok(elapsed.as_secs_f64())
+        });
+        self.define_fn("bigint", |(value,): (Value,)| {
+            let n = match &value {
+                Value::String(s) => BigInt::parse(s.trim(), 10),
+                Value::Number(n) if n.fract() == 0.0 => BigInt::parse(&format!("{n:.0}"), 10),
+                _ => value.to_bigint(),
+            };
+            n.map(Value::BigInt).ok_or_else(|| {
+                RuntimeError::new(format!("Cannot convert {} to a big integer.", value.repr()))
+            })
+        });
+        // Floats convert through their shortest representation, so
+        // `decimal(0.1)` is exactly `0.1d`.
+        self.define_fn("decimal", |(value,): (Value,)| {
+            let d = match &value {
+                Value::String(s) => Decimal::parse(s.trim()),
+                Value::Number(n) if n.is_finite() => Decimal::parse(&n.to_string()),
+                _ => value.to_decimal(),
+            };
+            d.map(Value::Decimal).ok_or_else(|| {
+                RuntimeError::new(format!("Cannot convert {} to a decimal.", value.repr()))
+            })
+        });
+        self.define_fn("round", |(d, places): (Decimal, i64)| {
+            u32::try_from(places)
+                .map(|places| d.round(places))
+                .map_err(|_| RuntimeError::new("Cannot round to a negative number of places."))
+        });
+    }
+
+    /// Exposes a Rust function to scripts as the global `name`. Calls with
+    /// the wrong number of arguments are rejected before `function` runs.
+    pub fn define_native<F>(&mut self, name: &str, arity: impl Into<Arity>, function: F)
+    where
+        F: Fn(&[Value]) -> Result<Value, RuntimeError> + 'static,
+    {
+        let native = NativeFunction::new(name, arity, function);
+        self.globals
+            .borrow_mut()
+            .define(name, Value::Function(Rc::new(native)));
+    }
+
+    /// Like [`Interpreter::define_native`], but the arguments are converted
+    /// to the Rust types `function` takes and its result back to a `Value`.
+    /// The arity follows from the argument type: a tuple takes that many
+    /// arguments and a `Vec` any number.
+    pub fn define_fn<A, R, F>(&mut self, name: &str, function: F)
+    where
+        A: FromArgs,
+        R: Into<Value>,
+        F: Fn(A) -> Result<R, RuntimeError> + 'static,
+    {
+        self.define_native(name, A::ARITY, move |arguments| {
+            function(A::from_args(arguments)?).map(Into::into)
+        });
+    }
+
+    /// Sends program output to `output` instead of stdout.
+    pub fn set_output(&mut self, output: impl Write + 'static) {
+        self.output = Box::new(output);
+    }
+
+    /// Forgets every global apart from the builtins, leaving the output sink
+    /// in place.
+    pub fn reset(&mut self) {
+        self.globals = Rc::new(RefCell::new(Environment::new()));
+        self.environment = Rc::clone(&self.globals);
+        self.define_builtins();
+    }
+
+    fn write_line(&mut self, text: &str, span: Span) -> Result<(), RuntimeError> {
+        writeln!(self.output, "{text}")
+            .and_then(|_| self.output.flush())
+            .map_err(|error| RuntimeError {
+                span: Some(span),
+                message: format!("Could not write output: {error}."),
+            })
+    }
+
+    /// The value of the global `name`, if it is defined.
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.globals.borrow().lookup(name)
+    }
+
+    /// Defines the global `name`, replacing any existing value.
+    pub fn set_global(&mut self, name: &str, value: impl Into<Value>) {
+        self.globals.borrow_mut().define(name, value.into());
+    }
+
+    /// Calls a function or class value from Rust, as the script expression
+    /// `callee(arguments...)` would.
+    pub fn call(&mut self, callee: &Value, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+        self.call_value(callee.clone(), arguments, None)
+    }
+
+    /// Checks the arity and performs a call. `paren` is where errors about
+    /// the call itself are reported, or `None` for calls made from Rust.
+    fn call_value(
+        &mut self,
+        callee: Value,
+        arguments: Vec<Value>,
+        paren: Option<Span>,
+    ) -> Result<Value, RuntimeError> {
+        let arity = match &callee {
+            Value::Function(function) => function.arity(),
+            Value::Class(class) => class.arity(),
+            _ => {
+                return Err(RuntimeError {
+                    span: paren,
+                    message: "Can only call functions and classes.".to_string(),
+                });
+            }
+        };
+
+        if let Arity::Fixed(arity) = arity
+            && arguments.len() != arity
+        {
+            return Err(RuntimeError {
+                span: paren,
+                message: format!("Expected {} arguments but got {}.", arity, arguments.len()),
+            });
+        }
+
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(RuntimeError {
+                span: paren,
+                message: "Stack overflow.".to_string(),
+            });
+        }
+
+        self.call_depth += 1;
+        let result = match callee {
+            Value::Class(class) => KestrlClass::instantiate(&class, self, arguments),
+            Value::Function(function) => function.call(self, arguments),
+            _ => unreachable!("callee was checked to be callable"),
+        };
+        self.call_depth -= 1;
+        result
     }
 
     pub fn evaluate(&mut self, expr: &expressions::Expr) -> Result<Value, RuntimeError> {
         expr.accept(self)
     }
 
+    fn look_up_variable(&self, name: &Token, depth: Option<usize>) -> Result<Value, RuntimeError> {
+        match depth {
+            Some(distance) => Ok(Environment::get_at(
+                &self.environment,
+                distance,
+                &name.lexeme,
+            )),
+            None => self.globals.borrow().get(name),
+        }
+    }
+
+    pub fn execute(&mut self, stmt: &Stmt) -> Result<(), Unwind> {
+        stmt.accept(self)
+    }
+
+    pub fn execute_block(
+        &mut self,
+        statements: &[Stmt],
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<(), Unwind> {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.execute(statement));
+        self.environment = previous;
+        result
+    }
+
     pub fn is_truthy(&mut self, value: &Value) -> bool {
         match value {
             Value::Nil => false,
@@ -153,13 +893,34 @@ impl Interpreter {
         if a == &Value::Nil {
             return false;
         }
+        // Numbers of different types are equal when their values are,
+        // following the same conversions as arithmetic.
+        if let Some(operands) = Operands::of(a, b) {
+            return operands.compare() == Some(Ordering::Equal);
+        }
         a == b
     }
 
-    pub fn interpret(&mut self, expr: &expressions::Expr) {
-        match self.evaluate(expr) {
-            Ok(value) => println!("Result: {value:?}"),
-            Err(e) => eprintln!("Runtime error: {}", e.message),
+    /// Runs a program. Returns the value of the final statement if it is an
+    /// expression statement, and `nil` otherwise.
+    pub fn interpret(&mut self, statements: &[Stmt]) -> Result<Value, RuntimeError> {
+        let mut last = Value::Nil;
+        for statement in statements {
+            if let Stmt::Expression(stmt) = statement {
+                last = self.evaluate(&stmt.expression)?;
+                if self.echo && last != Value::Nil {
+                    self.write_line(&last.repr(), stmt.span)?;
+                }
+                continue;
+            }
+
+            last = Value::Nil;
+            match self.execute(statement) {
+                Ok(()) => {}
+                Err(Unwind::Error(e)) => return Err(e),
+                Err(Unwind::Return(value)) => return Ok(value),
+            }
         }
+        Ok(last)
     }
 }
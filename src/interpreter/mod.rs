@@ -1,5 +1,18 @@
+pub mod ast_printer;
+pub mod bigint;
+pub mod callable;
+pub mod class;
+pub mod convert;
+pub mod decimal;
+pub mod diagnostics;
+pub mod environment;
 pub mod expressions;
+#[allow(clippy::module_inception)]
 pub mod interpreter;
+pub mod output;
 pub mod parser;
+pub mod resolver;
 pub mod scanner;
+pub mod span;
+pub mod statements;
 pub mod token_types;
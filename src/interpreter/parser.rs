@@ -1,40 +1,489 @@
 use crate::interpreter::{
-    expressions::{BinaryExpr, Expr, GroupingExpr, LiteralExpr, UnaryExpr},
+    diagnostics::{Diagnostic, PARSE_ERROR},
+    expressions::{
+        AssignExpr, BinaryExpr, CallExpr, Expr, GetExpr, GroupingExpr, InterpolationExpr,
+        LiteralExpr, LogicalExpr, SetExpr, SuperExpr, ThisExpr, UnaryExpr, VariableExpr,
+    },
     scanner::{Literal, Token},
+    span::Span,
+    statements::{
+        BlockStmt, ClassStmt, ExpressionStmt, FunctionStmt, IfStmt, PrintStmt, ReturnStmt, Stmt,
+        VarStmt, WhileStmt,
+    },
     token_types::TokenType,
 };
+use std::{cell::Cell, rc::Rc};
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub token: Token,
+    pub message: String,
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(error: ParseError) -> Self {
+        let diagnostic = Diagnostic::error(PARSE_ERROR, error.message, error.token.span);
+        match error.token.token_type {
+            TokenType::EOF => diagnostic
+                .with_note("reached the end of the input")
+                .at_eof(),
+            _ => diagnostic,
+        }
+    }
+}
+
+/// How deeply expressions, blocks and statement bodies may nest. The parser,
+/// resolver and interpreter all recurse over the syntax tree, so this must
+/// fit in [`crate::kestrl::STACK_SIZE`] with room left for calls.
+pub const MAX_NESTING: usize = 256;
 
 pub struct Parser {
     current: usize,
     tokens: Vec<Token>,
+    errors: Vec<ParseError>,
+    /// Number of nested constructs being parsed, see [`MAX_NESTING`].
+    depth: usize,
+    /// Set once the input has nested too deeply and the rest of it has been
+    /// skipped.
+    too_deep: bool,
 }
 
-#[allow(dead_code)]
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { current: 0, tokens }
+    /// Creates a parser over `tokens`. The scanner always terminates its
+    /// output with an EOF token, but one is appended here if it is missing so
+    /// that `peek` never runs off the end of a hand-built token list.
+    pub fn new(mut tokens: Vec<Token>) -> Self {
+        if tokens
+            .last()
+            .is_none_or(|token| token.token_type != TokenType::EOF)
+        {
+            let end = tokens.last().map_or(Span::default(), |token| token.span);
+            tokens.push(Token {
+                token_type: TokenType::EOF,
+                lexeme: String::new(),
+                literal: Literal::Nil,
+                span: Span::new(end.end, end.end, end.line.max(1), end.column.max(1)),
+            });
+        }
+
+        Parser {
+            current: 0,
+            tokens,
+            errors: Vec::new(),
+            depth: 0,
+            too_deep: false,
+        }
+    }
+
+    /// Parses the whole token stream. Syntax errors do not stop parsing: the
+    /// parser skips to the next statement boundary and keeps going so that
+    /// every error in the input is reported at once.
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, Vec<ParseError>> {
+        let mut statements = Vec::new();
+        while !self.is_at_end() {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
+        }
+
+        if self.errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    fn declaration(&mut self) -> Option<Stmt> {
+        let result = if self.match_token(&[TokenType::CLASS]) {
+            self.class_declaration()
+        } else if self.match_token(&[TokenType::FUN]) {
+            self.function("function")
+                .map(|function| Stmt::Function(Rc::new(function)))
+        } else if self.match_token(&[TokenType::VAR]) {
+            self.var_declaration()
+        } else {
+            self.statement()
+        };
+
+        match result {
+            Ok(statement) => Some(statement),
+            Err(error) => {
+                if !self.too_deep {
+                    self.errors.push(error);
+                }
+                self.synchronize();
+                None
+            }
+        }
+    }
+
+    fn class_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.previous().span;
+        let name = self
+            .consume(TokenType::IDENTIFIER, "Expect class name.")?
+            .clone();
+
+        let superclass = if self.match_token(&[TokenType::LESS]) {
+            let superclass_name = self
+                .consume(TokenType::IDENTIFIER, "Expect superclass name.")?
+                .clone();
+            Some(Expr::Variable(VariableExpr {
+                name: superclass_name,
+                depth: Cell::new(None),
+            }))
+        } else {
+            None
+        };
+
+        self.consume(TokenType::LEFT_BRACE, "Expect '{' before class body.")?;
+
+        let mut methods = Vec::new();
+        while !self.check(&TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            methods.push(Rc::new(self.function("method")?));
+        }
+
+        self.consume(TokenType::RIGHT_BRACE, "Expect '}' after class body.")?;
+
+        Ok(Stmt::Class(ClassStmt {
+            name,
+            superclass,
+            methods,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn function(&mut self, kind: &str) -> Result<FunctionStmt, ParseError> {
+        let start = self.peek().span;
+        let name = self
+            .consume(TokenType::IDENTIFIER, &format!("Expect {kind} name."))?
+            .clone();
+
+        self.consume(
+            TokenType::LEFT_PAREN,
+            &format!("Expect '(' after {kind} name."),
+        )?;
+        let mut params = Vec::new();
+        if !self.check(&TokenType::RIGHT_PAREN) {
+            loop {
+                params.push(
+                    self.consume(TokenType::IDENTIFIER, "Expect parameter name.")?
+                        .clone(),
+                );
+                if !self.match_token(&[TokenType::COMMA]) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after parameters.")?;
+
+        self.consume(
+            TokenType::LEFT_BRACE,
+            &format!("Expect '{{' before {kind} body."),
+        )?;
+        let body = self.block()?;
+
+        Ok(FunctionStmt {
+            name,
+            params,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    fn var_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.previous().span;
+        let name = self
+            .consume(TokenType::IDENTIFIER, "Expect variable name.")?
+            .clone();
+
+        let initializer = if self.match_token(&[TokenType::EQUAL]) {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        self.consume(
+            TokenType::SEMICOLON,
+            "Expect ';' after variable declaration.",
+        )?;
+        Ok(Stmt::Var(VarStmt {
+            name,
+            initializer,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        if self.match_token(&[TokenType::FOR]) {
+            return self.for_statement();
+        }
+        if self.match_token(&[TokenType::IF]) {
+            return self.if_statement();
+        }
+        if self.match_token(&[TokenType::PRINT]) {
+            return self.print_statement();
+        }
+        if self.match_token(&[TokenType::RETURN]) {
+            return self.return_statement();
+        }
+        if self.match_token(&[TokenType::WHILE]) {
+            return self.while_statement();
+        }
+        if self.match_token(&[TokenType::LEFT_BRACE]) {
+            let start = self.previous().span;
+            let statements = self.block()?;
+            return Ok(Stmt::Block(BlockStmt {
+                statements,
+                span: self.span_from(start),
+            }));
+        }
+        self.expression_statement()
+    }
+
+    // `for` loops are desugared into an equivalent `while` loop wrapped in blocks.
+    fn for_statement(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.previous().span;
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'for'.")?;
+
+        let initializer = if self.match_token(&[TokenType::SEMICOLON]) {
+            None
+        } else if self.match_token(&[TokenType::VAR]) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if self.check(&TokenType::SEMICOLON) {
+            None
+        } else {
+            Some(self.expr()?)
+        };
+        self.consume(TokenType::SEMICOLON, "Expect ';' after loop condition.")?;
+
+        let increment = if self.check(&TokenType::RIGHT_PAREN) {
+            None
+        } else {
+            Some(self.expr()?)
+        };
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after for clauses.")?;
+
+        let mut body = self.nested(Self::statement)?;
+        let span = self.span_from(start);
+
+        if let Some(increment) = increment {
+            body = Stmt::Block(BlockStmt {
+                statements: vec![
+                    body,
+                    Stmt::Expression(ExpressionStmt {
+                        span: increment.span(),
+                        expression: increment,
+                    }),
+                ],
+                span,
+            });
+        }
+
+        let condition = condition.unwrap_or(Expr::Literal(LiteralExpr {
+            value: Literal::Bool(true),
+            span: start,
+        }));
+        body = Stmt::While(Box::new(WhileStmt {
+            condition,
+            body,
+            span,
+        }));
+
+        if let Some(initializer) = initializer {
+            body = Stmt::Block(BlockStmt {
+                statements: vec![initializer, body],
+                span,
+            });
+        }
+
+        Ok(body)
+    }
+
+    /// An `else if` chain is read in a loop rather than by recursion, so a
+    /// long chain does not count towards [`MAX_NESTING`]. It still becomes
+    /// nested `IfStmt`s, each spanning to the end of the whole chain.
+    fn if_statement(&mut self) -> Result<Stmt, ParseError> {
+        let mut branches = Vec::new();
+        let mut else_branch = None;
+        loop {
+            let start = self.previous().span;
+            self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'if'.")?;
+            let condition = self.expr()?;
+            self.consume(TokenType::RIGHT_PAREN, "Expect ')' after if condition.")?;
+            let then_branch = self.nested(Self::statement)?;
+            branches.push((start, condition, then_branch));
+
+            if !self.match_token(&[TokenType::ELSE]) {
+                break;
+            }
+            if !self.match_token(&[TokenType::IF]) {
+                else_branch = Some(self.nested(Self::statement)?);
+                break;
+            }
+        }
+
+        let end = self.previous().span;
+        for (start, condition, then_branch) in branches.into_iter().rev() {
+            else_branch = Some(Stmt::If(Box::new(IfStmt {
+                condition,
+                then_branch,
+                else_branch,
+                span: start.to(end),
+            })));
+        }
+        Ok(else_branch.expect("an if statement has at least one branch"))
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.previous().span;
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'while'.")?;
+        let condition = self.expr()?;
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after condition.")?;
+        let body = self.nested(Self::statement)?;
+
+        Ok(Stmt::While(Box::new(WhileStmt {
+            condition,
+            body,
+            span: self.span_from(start),
+        })))
     }
 
-    pub fn parse(&mut self) -> Expr {
-        self.expr()
+    fn print_statement(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.previous().span;
+        let expression = self.expr()?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after value.")?;
+        Ok(Stmt::Print(PrintStmt {
+            expression,
+            span: self.span_from(start),
+        }))
     }
 
-    pub fn expr(&mut self) -> Expr {
-        self.equality()
+    fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous().clone();
+        let value = if self.check(&TokenType::SEMICOLON) {
+            None
+        } else {
+            Some(self.expr()?)
+        };
+
+        self.consume(TokenType::SEMICOLON, "Expect ';' after return value.")?;
+        Ok(Stmt::Return(ReturnStmt {
+            span: self.span_from(keyword.span),
+            keyword,
+            value,
+        }))
     }
 
-    fn equality(&mut self) -> Expr {
-        let mut expr = self.comparison();
+    fn expression_statement(&mut self) -> Result<Stmt, ParseError> {
+        let expression = self.expr()?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after expression.")?;
+        Ok(Stmt::Expression(ExpressionStmt {
+            span: self.span_from(expression.span()),
+            expression,
+        }))
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        self.nested(|parser| {
+            let mut statements = Vec::new();
+
+            while !parser.check(&TokenType::RIGHT_BRACE) && !parser.is_at_end() {
+                if let Some(statement) = parser.declaration() {
+                    statements.push(statement);
+                }
+            }
+
+            parser.consume(TokenType::RIGHT_BRACE, "Expect '}' after block.")?;
+            Ok(statements)
+        })
+    }
+
+    /// Every grouping, call argument, interpolation and assigned value is
+    /// parsed through here, so this is where expression nesting is counted.
+    pub fn expr(&mut self) -> Result<Expr, ParseError> {
+        self.nested(Self::assignment)
+    }
+
+    fn assignment(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.or()?;
+
+        if self.match_token(&[TokenType::EQUAL]) {
+            let equals = self.previous().clone();
+            let value = self.expr()?;
+
+            match expr {
+                Expr::Variable(variable) => {
+                    return Ok(Expr::Assign(Box::new(AssignExpr {
+                        name: variable.name,
+                        value,
+                        depth: Cell::new(None),
+                    })));
+                }
+                Expr::Get(get) => {
+                    return Ok(Expr::Set(Box::new(SetExpr {
+                        object: get.object,
+                        name: get.name,
+                        value,
+                    })));
+                }
+                // Reported without unwinding: the parser is not confused
+                // about where it is, so there is no need to synchronize.
+                _ => {
+                    let error = self.error(&equals, "Invalid assignment target.");
+                    self.errors.push(error);
+                }
+            }
+            return Ok(value);
+        }
+        Ok(expr)
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+
+        while self.match_token(&[TokenType::OR]) {
+            let operator = self.previous().clone();
+            let right = self.and()?;
+            expr = Expr::Logical(Box::new(LogicalExpr {
+                left: expr,
+                operator,
+                right,
+            }));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.equality()?;
+
+        while self.match_token(&[TokenType::AND]) {
+            let operator = self.previous().clone();
+            let right = self.equality()?;
+            expr = Expr::Logical(Box::new(LogicalExpr {
+                left: expr,
+                operator,
+                right,
+            }));
+        }
+        Ok(expr)
+    }
+
+    fn equality(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.comparison()?;
         while self.match_token(&[TokenType::BANG_EQUAL, TokenType::EQUAL_EQUAL]) {
             let operator = self.previous().clone();
-            let right = self.comparison();
+            let right = self.comparison()?;
             expr = Expr::Binary(Box::new(BinaryExpr {
                 left: expr,
                 operator,
                 right,
             }));
         }
-        expr
+        Ok(expr)
     }
 
     fn match_token(&mut self, types: &[TokenType]) -> bool {
@@ -70,11 +519,11 @@ impl Parser {
     }
 
     fn previous(&self) -> &Token {
-        &self.tokens[self.current - 1]
+        &self.tokens[self.current.saturating_sub(1)]
     }
 
-    fn comparison(&mut self) -> Expr {
-        let mut expr = self.term();
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.term()?;
 
         while self.match_token(&[
             TokenType::GREATER,
@@ -83,89 +532,262 @@ impl Parser {
             TokenType::LESS_EQUAL,
         ]) {
             let operator = self.previous().clone();
-            let right = self.term();
+            let right = self.term()?;
             expr = Expr::Binary(Box::new(BinaryExpr {
                 left: expr,
                 operator,
                 right,
             }));
         }
-        expr
+        Ok(expr)
     }
 
-    fn term(&mut self) -> Expr {
-        let mut expr = self.factor();
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.factor()?;
 
         while self.match_token(&[TokenType::MINUS, TokenType::PLUS]) {
             let operator = self.previous().clone();
-            let right = self.factor();
+            let right = self.factor()?;
             expr = Expr::Binary(Box::new(BinaryExpr {
                 left: expr,
                 operator,
                 right,
             }));
         }
-        expr
+        Ok(expr)
     }
 
-    fn factor(&mut self) -> Expr {
-        let mut expr = self.unary();
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
 
-        while self.match_token(&[TokenType::SLASH, TokenType::STAR]) {
+        while self.match_token(&[
+            TokenType::SLASH,
+            TokenType::STAR,
+            TokenType::TILDE_SLASH,
+            TokenType::PERCENT,
+        ]) {
             let operator = self.previous().clone();
-            let right = self.unary();
+            let right = self.unary()?;
             expr = Expr::Binary(Box::new(BinaryExpr {
                 left: expr,
                 operator,
                 right,
             }));
         }
-        expr
+        Ok(expr)
     }
 
-    fn unary(&mut self) -> Expr {
+    fn unary(&mut self) -> Result<Expr, ParseError> {
         if self.match_token(&[TokenType::BANG, TokenType::MINUS]) {
             let operator = self.previous().clone();
-            let right = self.unary();
-            return Expr::Unary(Box::new(UnaryExpr { operator, right }));
+            let right = self.nested(Self::unary)?;
+            return Ok(Expr::Unary(Box::new(UnaryExpr { operator, right })));
+        }
+        self.call()
+    }
+
+    fn call(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.match_token(&[TokenType::LEFT_PAREN]) {
+                expr = self.finish_call(expr)?;
+            } else if self.match_token(&[TokenType::DOT]) {
+                let name = self
+                    .consume(TokenType::IDENTIFIER, "Expect property name after '.'.")?
+                    .clone();
+                expr = Expr::Get(Box::new(GetExpr { object: expr, name }));
+            } else {
+                break;
+            }
         }
-        self.primary()
+        Ok(expr)
     }
 
-    fn primary(&mut self) -> Expr {
+    fn finish_call(&mut self, callee: Expr) -> Result<Expr, ParseError> {
+        let mut arguments = Vec::new();
+        if !self.check(&TokenType::RIGHT_PAREN) {
+            loop {
+                arguments.push(self.expr()?);
+                if !self.match_token(&[TokenType::COMMA]) {
+                    break;
+                }
+            }
+        }
+
+        let paren = self
+            .consume(TokenType::RIGHT_PAREN, "Expect ')' after arguments.")?
+            .clone();
+
+        Ok(Expr::Call(Box::new(CallExpr {
+            callee,
+            paren,
+            arguments,
+        })))
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        if let Some(brace) = self.interpolation_end() {
+            return Err(self.error(&brace, "Expect expression."));
+        }
         if self.match_token(&[TokenType::FALSE]) {
-            return Expr::Literal(LiteralExpr {
+            return Ok(Expr::Literal(LiteralExpr {
                 value: Literal::Bool(false),
-            });
+                span: self.previous().span,
+            }));
         }
         if self.match_token(&[TokenType::TRUE]) {
-            return Expr::Literal(LiteralExpr {
+            return Ok(Expr::Literal(LiteralExpr {
                 value: Literal::Bool(true),
-            });
+                span: self.previous().span,
+            }));
         }
         if self.match_token(&[TokenType::NIL]) {
-            return Expr::Literal(LiteralExpr {
+            return Ok(Expr::Literal(LiteralExpr {
                 value: Literal::Nil,
-            });
+                span: self.previous().span,
+            }));
         }
         if self.match_token(&[TokenType::NUMBER, TokenType::STRING]) {
-            return Expr::Literal(LiteralExpr {
+            return Ok(Expr::Literal(LiteralExpr {
                 value: self.previous().literal.clone(),
-            });
+                span: self.previous().span,
+            }));
+        }
+        if self.match_token(&[TokenType::INTERPOLATION]) {
+            return self.interpolation();
+        }
+        if self.match_token(&[TokenType::SUPER]) {
+            let keyword = self.previous().clone();
+            self.consume(TokenType::DOT, "Expect '.' after 'super'.")?;
+            let method = self
+                .consume(TokenType::IDENTIFIER, "Expect superclass method name.")?
+                .clone();
+            return Ok(Expr::Super(SuperExpr {
+                keyword,
+                method,
+                depth: Cell::new(None),
+            }));
+        }
+        if self.match_token(&[TokenType::THIS]) {
+            return Ok(Expr::This(ThisExpr {
+                keyword: self.previous().clone(),
+                depth: Cell::new(None),
+            }));
+        }
+        if self.match_token(&[TokenType::IDENTIFIER]) {
+            return Ok(Expr::Variable(VariableExpr {
+                name: self.previous().clone(),
+                depth: Cell::new(None),
+            }));
         }
         if self.match_token(&[TokenType::LEFT_PAREN]) {
-            let expr = self.expr();
-            self.consume(TokenType::RIGHT_PAREN, "Expect ')' after expression.");
-            return Expr::Grouping(Box::new(GroupingExpr { expression: expr }));
+            let start = self.previous().span;
+            let expr = self.expr()?;
+            self.consume(TokenType::RIGHT_PAREN, "Expect ')' after expression.")?;
+            return Ok(Expr::Grouping(Box::new(GroupingExpr {
+                expression: expr,
+                span: self.span_from(start),
+            })));
         }
-        panic!("Expect expression.");
+        Err(self.error(self.peek(), "Expect expression."))
     }
 
-    fn consume(&mut self, token_type: TokenType, message: &str) -> &Token {
+    /// Parses the rest of an interpolated string once its first INTERPOLATION
+    /// token has been consumed. The scanner emits `"a ${x} b ${y} c"` as
+    /// INTERPOLATION("a "), x, INTERPOLATION(" b "), y, STRING(" c").
+    fn interpolation(&mut self) -> Result<Expr, ParseError> {
+        let start = self.previous().span;
+        let mut parts = Vec::new();
+        loop {
+            self.push_segment(&mut parts);
+            parts.push(self.expr()?);
+            if !self.match_token(&[TokenType::INTERPOLATION]) {
+                break;
+            }
+        }
+        self.consume(
+            TokenType::STRING,
+            "Expect '}' after interpolated expression.",
+        )?;
+        self.push_segment(&mut parts);
+
+        Ok(Expr::Interpolation(InterpolationExpr {
+            parts,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// If the next token is the part of a string that follows a `}` closing
+    /// an interpolation, returns a token for just that `}`. Such a token can
+    /// never start an expression.
+    fn interpolation_end(&self) -> Option<Token> {
+        let token = self.peek();
+        let resumes_string = matches!(
+            token.token_type,
+            TokenType::STRING | TokenType::INTERPOLATION
+        ) && token.lexeme.starts_with('}');
+        resumes_string.then(|| {
+            let mut brace = token.clone();
+            brace.lexeme = "}".to_string();
+            brace.span.end = brace.span.start + 1;
+            brace
+        })
+    }
+
+    /// Adds the text of the string segment just consumed, unless it is empty.
+    fn push_segment(&self, parts: &mut Vec<Expr>) {
+        let segment = self.previous();
+        if let Literal::Str(text) = &segment.literal
+            && !text.is_empty()
+        {
+            parts.push(Expr::Literal(LiteralExpr {
+                value: segment.literal.clone(),
+                span: segment.span,
+            }));
+        }
+    }
+
+    /// Runs `parse` one level deeper, failing instead once the input nests
+    /// more than [`MAX_NESTING`] levels. That error is reported only once.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth >= MAX_NESTING {
+            // Skip the rest of the input rather than report every unclosed
+            // bracket and block on the way back out.
+            let error = self.error(self.peek(), "Too much nesting.");
+            if !self.too_deep {
+                self.errors.push(error.clone());
+                self.too_deep = true;
+            }
+            self.current = self.tokens.len() - 1;
+            return Err(error);
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn consume(&mut self, token_type: TokenType, message: &str) -> Result<&Token, ParseError> {
         if self.check(&token_type) {
-            return self.advance();
+            return Ok(self.advance());
+        }
+        Err(self.error(self.peek(), message))
+    }
+
+    /// Span from `start` through the most recently consumed token.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.previous().span)
+    }
+
+    fn error(&self, token: &Token, message: &str) -> ParseError {
+        ParseError {
+            token: token.clone(),
+            message: message.to_string(),
         }
-        panic!("{}", message);
     }
 
     fn synchronize(&mut self) {
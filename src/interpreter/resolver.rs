@@ -0,0 +1,327 @@
+use crate::interpreter::{
+    diagnostics::{Diagnostic, RESOLVE_ERROR},
+    expressions::{self, Expr},
+    scanner::Token,
+    statements::{self, FunctionStmt, Stmt},
+};
+use std::{collections::HashMap, rc::Rc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+/// Static pass run between the parser and the interpreter. It records how
+/// many scopes separate each local variable reference from its declaration
+/// and reports scope errors before any code is executed.
+pub struct Resolver<'a> {
+    scopes: Vec<HashMap<String, bool>>,
+    current_function: FunctionType,
+    current_class: ClassType,
+    reporter: &'a mut dyn FnMut(Diagnostic),
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(reporter: &'a mut dyn FnMut(Diagnostic)) -> Self {
+        Resolver {
+            scopes: Vec::new(),
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+            reporter,
+        }
+    }
+
+    pub fn resolve(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            self.resolve_stmt(statement);
+        }
+    }
+
+    fn resolve_stmt(&mut self, stmt: &Stmt) {
+        stmt.accept(self);
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr) {
+        expr.accept(self);
+    }
+
+    fn resolve_function(&mut self, function: &FunctionStmt, function_type: FunctionType) {
+        let enclosing_function = self.current_function;
+        self.current_function = function_type;
+
+        self.begin_scope();
+        for param in &function.params {
+            self.declare(param);
+            self.define(param);
+        }
+        self.resolve(&function.body);
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+    }
+
+    /// Returns the number of scopes between the innermost scope and the one
+    /// declaring `name`, or `None` if the name is assumed to be global.
+    fn resolve_local(&self, name: &Token) -> Option<usize> {
+        let key = name.lexeme.as_str();
+        self.scopes
+            .iter()
+            .rev()
+            .position(|scope| scope.contains_key(key))
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token) {
+        let key = name.lexeme.as_str();
+        let Some(scope) = self.scopes.last_mut() else {
+            return;
+        };
+
+        if scope.contains_key(key) {
+            self.report(
+                Diagnostic::error(
+                    RESOLVE_ERROR,
+                    "Already a variable with this name in this scope.",
+                    name.span,
+                )
+                .with_help("rename one of the variables or assign to the existing one"),
+            );
+            return;
+        }
+        scope.insert(key.to_string(), false);
+    }
+
+    fn define(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), true);
+        }
+    }
+
+    fn error(&mut self, token: &Token, message: &str) {
+        self.report(Diagnostic::error(RESOLVE_ERROR, message, token.span));
+    }
+
+    fn report(&mut self, diagnostic: Diagnostic) {
+        (self.reporter)(diagnostic);
+    }
+}
+
+impl expressions::Visitor<()> for Resolver<'_> {
+    fn visit_binary_expr(&mut self, expr: &expressions::BinaryExpr) {
+        self.resolve_expr(&expr.left);
+        self.resolve_expr(&expr.right);
+    }
+
+    fn visit_unary_expr(&mut self, expr: &expressions::UnaryExpr) {
+        self.resolve_expr(&expr.right);
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &expressions::GroupingExpr) {
+        self.resolve_expr(&expr.expression);
+    }
+
+    fn visit_literal_expr(&mut self, _expr: &expressions::LiteralExpr) {}
+
+    fn visit_variable_expr(&mut self, expr: &expressions::VariableExpr) {
+        let key = expr.name.lexeme.as_str();
+        let in_own_initializer = self
+            .scopes
+            .last()
+            .is_some_and(|scope| scope.get(key) == Some(&false));
+        if in_own_initializer {
+            self.error(
+                &expr.name,
+                "Can't read local variable in its own initializer.",
+            );
+        }
+
+        expr.depth.set(self.resolve_local(&expr.name));
+    }
+
+    fn visit_assign_expr(&mut self, expr: &expressions::AssignExpr) {
+        self.resolve_expr(&expr.value);
+        expr.depth.set(self.resolve_local(&expr.name));
+    }
+
+    fn visit_logical_expr(&mut self, expr: &expressions::LogicalExpr) {
+        self.resolve_expr(&expr.left);
+        self.resolve_expr(&expr.right);
+    }
+
+    fn visit_call_expr(&mut self, expr: &expressions::CallExpr) {
+        self.resolve_expr(&expr.callee);
+        for argument in &expr.arguments {
+            self.resolve_expr(argument);
+        }
+    }
+
+    fn visit_get_expr(&mut self, expr: &expressions::GetExpr) {
+        self.resolve_expr(&expr.object);
+    }
+
+    fn visit_set_expr(&mut self, expr: &expressions::SetExpr) {
+        self.resolve_expr(&expr.value);
+        self.resolve_expr(&expr.object);
+    }
+
+    fn visit_this_expr(&mut self, expr: &expressions::ThisExpr) {
+        if self.current_class == ClassType::None {
+            self.error(&expr.keyword, "Can't use 'this' outside of a class.");
+            return;
+        }
+
+        expr.depth.set(self.resolve_local(&expr.keyword));
+    }
+
+    fn visit_super_expr(&mut self, expr: &expressions::SuperExpr) {
+        match self.current_class {
+            ClassType::None => {
+                self.error(&expr.keyword, "Can't use 'super' outside of a class.");
+                return;
+            }
+            ClassType::Class => {
+                self.error(
+                    &expr.keyword,
+                    "Can't use 'super' in a class with no superclass.",
+                );
+                return;
+            }
+            ClassType::Subclass => {}
+        }
+
+        expr.depth.set(self.resolve_local(&expr.keyword));
+    }
+
+    fn visit_interpolation_expr(&mut self, expr: &expressions::InterpolationExpr) {
+        for part in &expr.parts {
+            self.resolve_expr(part);
+        }
+    }
+}
+
+impl statements::Visitor<()> for Resolver<'_> {
+    fn visit_expression_stmt(&mut self, stmt: &statements::ExpressionStmt) {
+        self.resolve_expr(&stmt.expression);
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &statements::PrintStmt) {
+        self.resolve_expr(&stmt.expression);
+    }
+
+    fn visit_var_stmt(&mut self, stmt: &statements::VarStmt) {
+        self.declare(&stmt.name);
+        if let Some(initializer) = &stmt.initializer {
+            self.resolve_expr(initializer);
+        }
+        self.define(&stmt.name);
+    }
+
+    fn visit_block_stmt(&mut self, stmt: &statements::BlockStmt) {
+        self.begin_scope();
+        self.resolve(&stmt.statements);
+        self.end_scope();
+    }
+
+    fn visit_if_stmt(&mut self, stmt: &statements::IfStmt) {
+        self.resolve_expr(&stmt.condition);
+        self.resolve_stmt(&stmt.then_branch);
+        if let Some(else_branch) = &stmt.else_branch {
+            self.resolve_stmt(else_branch);
+        }
+    }
+
+    fn visit_while_stmt(&mut self, stmt: &statements::WhileStmt) {
+        self.resolve_expr(&stmt.condition);
+        self.resolve_stmt(&stmt.body);
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Rc<FunctionStmt>) {
+        self.declare(&stmt.name);
+        self.define(&stmt.name);
+
+        self.resolve_function(stmt, FunctionType::Function);
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &statements::ReturnStmt) {
+        if self.current_function == FunctionType::None {
+            self.report(
+                Diagnostic::error(
+                    RESOLVE_ERROR,
+                    "Can't return from top-level code.",
+                    stmt.keyword.span,
+                )
+                .with_note("'return' is only allowed inside a function or method body"),
+            );
+        }
+
+        if let Some(value) = &stmt.value {
+            if self.current_function == FunctionType::Initializer {
+                self.error(&stmt.keyword, "Can't return a value from an initializer.");
+            }
+            self.resolve_expr(value);
+        }
+    }
+
+    fn visit_class_stmt(&mut self, stmt: &statements::ClassStmt) {
+        let enclosing_class = self.current_class;
+        self.current_class = ClassType::Class;
+
+        self.declare(&stmt.name);
+        self.define(&stmt.name);
+
+        if let Some(superclass) = &stmt.superclass {
+            if let Expr::Variable(variable) = superclass
+                && variable.name.lexeme == stmt.name.lexeme
+            {
+                self.error(&variable.name, "A class can't inherit from itself.");
+            }
+
+            self.current_class = ClassType::Subclass;
+            self.resolve_expr(superclass);
+
+            self.begin_scope();
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.insert("super".to_string(), true);
+            }
+        }
+
+        self.begin_scope();
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert("this".to_string(), true);
+        }
+
+        for method in &stmt.methods {
+            let function_type = if method.name.lexeme == "init" {
+                FunctionType::Initializer
+            } else {
+                FunctionType::Method
+            };
+            self.resolve_function(method, function_type);
+        }
+
+        self.end_scope();
+
+        if stmt.superclass.is_some() {
+            self.end_scope();
+        }
+
+        self.current_class = enclosing_class;
+    }
+}
@@ -1,4 +1,10 @@
-use crate::interpreter::token_types::TokenType;
+use crate::interpreter::{
+    bigint::BigInt,
+    decimal::Decimal,
+    diagnostics::{Diagnostic, SCAN_ERROR},
+    span::Span,
+    token_types::TokenType,
+};
 use core::f64;
 use std::{
     collections::HashMap,
@@ -8,8 +14,9 @@ use std::{
 #[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
+    pub lexeme: String,
     pub literal: Literal,
-    pub line: usize,
+    pub span: Span,
 }
 
 impl Display for Token {
@@ -20,6 +27,9 @@ impl Display for Token {
 
 #[derive(Debug, Clone)]
 pub enum Literal {
+    Int(i64),
+    BigInt(Box<BigInt>),
+    Decimal(Box<Decimal>),
     Number(f64),
     Str(String),
     Bool(bool),
@@ -27,7 +37,27 @@ pub enum Literal {
     Nil,
 }
 
-pub type ErrorReporter = dyn FnMut(usize, &str);
+/// Reserved words and the token types they scan to.
+pub const KEYWORDS: &[(&str, TokenType)] = &[
+    ("and", TokenType::AND),
+    ("class", TokenType::CLASS),
+    ("else", TokenType::ELSE),
+    ("false", TokenType::FALSE),
+    ("for", TokenType::FOR),
+    ("fun", TokenType::FUN),
+    ("if", TokenType::IF),
+    ("nil", TokenType::NIL),
+    ("or", TokenType::OR),
+    ("print", TokenType::PRINT),
+    ("return", TokenType::RETURN),
+    ("super", TokenType::SUPER),
+    ("this", TokenType::THIS),
+    ("true", TokenType::TRUE),
+    ("var", TokenType::VAR),
+    ("while", TokenType::WHILE),
+];
+
+pub type ErrorReporter = dyn FnMut(Diagnostic);
 
 pub struct Scanner<'a> {
     source: &'a str,
@@ -35,7 +65,13 @@ pub struct Scanner<'a> {
     start: usize,
     current: usize,
     line: usize,
+    line_start: usize,
+    start_line: usize,
+    start_column: usize,
     keywords: HashMap<&'a str, TokenType>,
+    /// One entry per `${` interpolation being scanned, innermost last: the
+    /// number of `{` opened inside it so far and where it started.
+    interpolations: Vec<(usize, Span)>,
 }
 
 impl<'a> Scanner<'a> {
@@ -46,35 +82,31 @@ impl<'a> Scanner<'a> {
             start: 0,
             current: 0,
             line: 1,
-            keywords: HashMap::from([
-                ("and", TokenType::AND),
-                ("class", TokenType::CLASS),
-                ("else", TokenType::ELSE),
-                ("false", TokenType::FALSE),
-                ("for", TokenType::FOR),
-                ("fun", TokenType::FUN),
-                ("if", TokenType::IF),
-                ("nil", TokenType::NIL),
-                ("or", TokenType::OR),
-                ("print", TokenType::PRINT),
-                ("return", TokenType::RETURN),
-                ("super", TokenType::SUPER),
-                ("this", TokenType::THIS),
-                ("true", TokenType::TRUE),
-                ("var", TokenType::VAR),
-                ("while", TokenType::WHILE),
-            ]),
+            line_start: 0,
+            start_line: 1,
+            start_column: 1,
+            keywords: HashMap::from_iter(KEYWORDS.iter().cloned()),
+            interpolations: Vec::new(),
         }
     }
 
-    pub fn advance(&mut self) -> char {
-        let c = self.source[self.current..].chars().next().unwrap_or('\0');
+    /// Creates a scanner whose positions start at `line`, for sources that
+    /// are a fragment of a larger input such as a single REPL entry.
+    pub fn with_line(source: &'a str, line: usize) -> Self {
+        let mut scanner = Scanner::new(source);
+        scanner.line = line;
+        scanner.start_line = line;
+        scanner
+    }
 
-        if c != '\0' {
-            self.current += c.len_utf8();
+    pub fn advance(&mut self) -> char {
+        match self.source[self.current..].chars().next() {
+            Some(c) => {
+                self.current += c.len_utf8();
+                c
+            }
+            None => '\0',
         }
-
-        c
     }
 
     pub fn peek(&self) -> char {
@@ -86,24 +118,31 @@ impl<'a> Scanner<'a> {
     }
 
     pub fn add_token(&mut self, token_type: TokenType, token: Option<Literal>) {
-        match token {
-            Some(t) => {
-                self.tokens.push(Token {
-                    token_type,
-                    line: self.line,
-                    literal: t,
-                });
-            }
-            None => {
-                let token_text = &self.source[self.start..self.current];
+        let token_text = &self.source[self.start..self.current];
+        let literal = token.unwrap_or_else(|| Literal::Unknown(token_text.to_string()));
+
+        self.tokens.push(Token {
+            token_type,
+            lexeme: token_text.to_string(),
+            literal,
+            span: self.token_span(),
+        });
+    }
 
-                self.tokens.push(Token {
-                    token_type,
-                    line: self.line,
-                    literal: Literal::Unknown(token_text.to_string()),
-                });
-            }
-        }
+    /// Span of the lexeme currently being scanned, from `start` to `current`.
+    pub fn token_span(&self) -> Span {
+        Span::new(self.start, self.current, self.start_line, self.start_column)
+    }
+
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
+    fn mark_start(&mut self) {
+        self.start = self.current;
+        self.start_line = self.line;
+        self.start_column = self.source[self.line_start..self.start].chars().count() + 1;
     }
 
     pub fn is_at_end(&self) -> bool {
@@ -124,21 +163,41 @@ impl<'a> Scanner<'a> {
 
     pub fn scan_token<T>(&mut self, reporter: &mut T)
     where
-        T: FnMut(usize, &str),
+        T: FnMut(Diagnostic),
     {
         let c = self.advance();
 
         match c {
             '(' => self.add_token(TokenType::LEFT_PAREN, Some(Literal::Str(c.to_string()))),
             ')' => self.add_token(TokenType::RIGHT_PAREN, Some(Literal::Str(c.to_string()))),
-            '{' => self.add_token(TokenType::LEFT_BRACE, Some(Literal::Str(c.to_string()))),
-            '}' => self.add_token(TokenType::RIGHT_BRACE, Some(Literal::Str(c.to_string()))),
+            '{' => {
+                if let Some((depth, _)) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+                self.add_token(TokenType::LEFT_BRACE, Some(Literal::Str(c.to_string())));
+            }
+            '}' => match self.interpolations.last_mut() {
+                // The `}` closing an interpolation resumes the string.
+                Some((0, _)) => {
+                    self.interpolations.pop();
+                    self.string(reporter);
+                }
+                Some((depth, _)) => {
+                    *depth -= 1;
+                    self.add_token(TokenType::RIGHT_BRACE, Some(Literal::Str(c.to_string())));
+                }
+                None => self.add_token(TokenType::RIGHT_BRACE, Some(Literal::Str(c.to_string()))),
+            },
             ',' => self.add_token(TokenType::COMMA, Some(Literal::Str(c.to_string()))),
             '.' => self.add_token(TokenType::DOT, Some(Literal::Str(c.to_string()))),
             '-' => self.add_token(TokenType::MINUS, Some(Literal::Str(c.to_string()))),
             '+' => self.add_token(TokenType::PLUS, Some(Literal::Str(c.to_string()))),
             ';' => self.add_token(TokenType::SEMICOLON, Some(Literal::Str(c.to_string()))),
             '*' => self.add_token(TokenType::STAR, Some(Literal::Str(c.to_string()))),
+            '%' => self.add_token(TokenType::PERCENT, Some(Literal::Str(c.to_string()))),
+            '~' if self.match_next('/') => {
+                self.add_token(TokenType::TILDE_SLASH, Some(Literal::Str("~/".to_string())))
+            }
             '!' => {
                 let token_type = if self.match_next('=') {
                     TokenType::BANG_EQUAL
@@ -202,24 +261,31 @@ impl<'a> Scanner<'a> {
                 }
             }
             '"' => {
-                self.string(reporter);
+                if self.peek() == '"' && self.peek_next() == '"' {
+                    self.raw_string(reporter);
+                } else {
+                    self.string(reporter);
+                }
             }
-            '\0' => (),
             ' ' | '\r' | '\t' => {
                 self.start = self.current;
             }
             '\n' => {
-                self.line += 1;
+                self.new_line();
                 self.start = self.current;
             }
             _ => {
                 if self.is_digit(c) {
-                    self.number();
+                    self.number(reporter);
                 } else if self.is_alpha(c) {
                     self.identifier();
                 } else {
-                    let error_message = format!("Unexpected character: '{c}'");
-                    reporter(self.line, &error_message);
+                    let error_message = format!("Unexpected character: '{}'", c.escape_debug());
+                    reporter(Diagnostic::error(
+                        SCAN_ERROR,
+                        error_message,
+                        self.token_span(),
+                    ));
                 }
             }
         }
@@ -251,76 +317,424 @@ impl<'a> Scanner<'a> {
         }
     }
 
-    pub fn number(&mut self) {
-        while self.is_digit(self.peek()) {
-            self.advance();
+    /// A number literal: decimal with an optional fraction and exponent
+    /// (`1.5e-9`), or an integer in hex, binary or octal (`0xFF`, `0b1010`,
+    /// `0o755`). Digits may be separated with `_`, as in `1_000_000`.
+    /// Literals without a fraction or exponent are integers. An `n` suffix
+    /// makes a big integer (`2n`) and a `d` suffix a decimal (`19.99d`).
+    pub fn number<T>(&mut self, reporter: &mut T)
+    where
+        T: FnMut(Diagnostic),
+    {
+        let first = self.source[self.start..].chars().next().unwrap_or('0');
+        let radix = match (first, self.peek()) {
+            ('0', 'x' | 'X') => Some((16, "hexadecimal")),
+            ('0', 'b' | 'B') => Some((2, "binary")),
+            ('0', 'o' | 'O') => Some((8, "octal")),
+            _ => None,
+        };
+
+        let value = match radix {
+            Some((radix, name)) => {
+                self.advance();
+                self.radix_number(radix, name)
+            }
+            None => self.decimal_number(),
+        };
+
+        match value {
+            Ok(literal) => self.add_token(TokenType::NUMBER, Some(literal)),
+            Err(NumberError { message, help }) => {
+                // Swallow the rest of the literal so it is reported once.
+                while self.is_alphanumeric(self.peek()) {
+                    self.advance();
+                }
+                reporter(Diagnostic::error(SCAN_ERROR, message, self.token_span()).with_help(help))
+            }
         }
+    }
 
+    fn decimal_number(&mut self) -> Result<Literal, NumberError> {
+        self.digits(self.start, |c| c.is_ascii_digit())?;
+        let mut fraction = false;
+        let mut exponent = false;
         if self.peek() == '.' && self.is_digit(self.peek_next()) {
+            fraction = true;
             self.advance();
+            self.digits(self.current, |c| c.is_ascii_digit())?;
+        }
 
-            while self.is_digit(self.peek()) {
+        if matches!(self.peek(), 'e' | 'E') {
+            exponent = true;
+            self.advance();
+            if matches!(self.peek(), '+' | '-') {
                 self.advance();
             }
+            let exponent_start = self.current;
+            self.digits(exponent_start, |c| c.is_ascii_digit())?;
+            if exponent_start == self.current {
+                return Err(number_error(
+                    "Expected digits after the exponent.",
+                    "write an exponent like 1e9 or 2.5e-3",
+                ));
+            }
+        }
+        let text = self.source[self.start..self.current].replace('_', "");
+        match self.number_suffix()? {
+            Some('n') if fraction || exponent => {
+                return Err(number_error(
+                    "Big integer literals cannot have a fraction or exponent.",
+                    "write big integers like 123n, or use a 'd' suffix for a decimal",
+                ));
+            }
+            Some('n') => {
+                return BigInt::parse(&text, 10)
+                    .map(|n| Literal::BigInt(Box::new(n)))
+                    .ok_or_else(invalid_number);
+            }
+            Some(_) if exponent => {
+                return Err(number_error(
+                    "Decimal literals cannot have an exponent.",
+                    "write decimals like 19.99d",
+                ));
+            }
+            Some(_) => {
+                return Decimal::parse(&text)
+                    .map(|d| Literal::Decimal(Box::new(d)))
+                    .ok_or_else(invalid_number);
+            }
+            None => {}
         }
 
-        let value = &self.source[self.start..self.current]
-            .parse::<f64>()
-            .unwrap();
-        self.add_token(TokenType::NUMBER, Some(Literal::Number(*value)));
+        if !fraction && !exponent {
+            return text.parse::<i64>().map(Literal::Int).map_err(|_| {
+                number_error(
+                    "Integer literal is too large.",
+                    "integers must be below 2^63; add an 'n' suffix for a big integer",
+                )
+            });
+        }
+        match text.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Literal::Number(value)),
+            Ok(_) => Err(number_error(
+                "Number literal is too large.",
+                "numbers must be below about 1.8e308",
+            )),
+            Err(_) => Err(invalid_number()),
+        }
     }
 
-    pub fn peek_next(&self) -> char {
-        if self.current + 1 >= self.source.len() {
-            '\0'
-        } else {
-            self.source[self.current + 1..]
-                .chars()
-                .next()
-                .unwrap_or('\0')
+    fn radix_number(&mut self, radix: u32, name: &str) -> Result<Literal, NumberError> {
+        let prefix = &self.source[self.start..self.current];
+        let help = format!("write {name} literals like {}", radix_example(radix));
+        let digits_start = self.current;
+        // Take every letter and digit so that `0xFG` is one bad literal
+        // rather than `0xF` followed by an identifier.
+        let separated = self.digits(digits_start, |c| c.is_ascii_alphanumeric());
+
+        let mut digits = self.source[digits_start..self.current].replace('_', "");
+        // `n` is never a digit, so a trailing one is the big integer suffix.
+        let big = digits.ends_with('n');
+        if big {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            return Err(number_error(
+                format!("Expected {name} digits after '{prefix}'."),
+                help,
+            ));
+        }
+        separated?;
+        if let Some(bad) = digits.chars().find(|c| !c.is_digit(radix)) {
+            return Err(number_error(
+                format!("Invalid digit '{bad}' in {name} literal."),
+                help,
+            ));
+        }
+
+        if big {
+            return BigInt::parse(&digits, radix)
+                .map(|n| Literal::BigInt(Box::new(n)))
+                .ok_or_else(invalid_number);
+        }
+        i64::from_str_radix(&digits, radix)
+            .map(Literal::Int)
+            .map_err(|_| {
+                number_error(
+                    format!("{} literal is too large.", capitalize(name)),
+                    "integers must be below 2^63; add an 'n' suffix for a big integer",
+                )
+            })
+    }
+
+    /// Consumes digits accepted by `is_digit` along with `_` separators, then
+    /// checks that the run beginning at `run_start` neither starts nor ends
+    /// with a separator.
+    fn digits(
+        &mut self,
+        run_start: usize,
+        is_digit: impl Fn(char) -> bool,
+    ) -> Result<(), NumberError> {
+        while is_digit(self.peek()) || self.peek() == '_' {
+            self.advance();
+        }
+        let run = &self.source[run_start..self.current];
+        if run.starts_with('_') || run.ends_with('_') {
+            return Err(number_error(
+                "Misplaced '_' in number literal.",
+                "'_' can only separate digits, as in 1_000_000",
+            ));
+        }
+        Ok(())
+    }
+
+    /// The `n` or `d` type suffix after a number, if any. Other letters
+    /// directly after a number, such as `12px`, are rejected.
+    fn number_suffix(&mut self) -> Result<Option<char>, NumberError> {
+        if !self.is_alpha(self.peek()) {
+            return Ok(None);
+        }
+        let suffix_start = self.current;
+        while self.is_alphanumeric(self.peek()) {
+            self.advance();
+        }
+        match &self.source[suffix_start..self.current] {
+            "n" => Ok(Some('n')),
+            "d" => Ok(Some('d')),
+            suffix => Err(number_error(
+                format!("Invalid suffix '{suffix}' on number literal."),
+                "use 'n' for a big integer or 'd' for a decimal, or separate the number from the name that follows it",
+            )),
         }
     }
 
+    pub fn peek_next(&self) -> char {
+        self.source[self.current..].chars().nth(1).unwrap_or('\0')
+    }
+
     pub fn string<T>(&mut self, reporter: &mut T)
     where
-        T: FnMut(usize, &str),
+        T: FnMut(Diagnostic),
     {
-        while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1;
+        let mut value = String::new();
+        loop {
+            if self.is_at_end() {
+                reporter(
+                    Diagnostic::error(SCAN_ERROR, "Unterminated string.", self.token_span())
+                        .with_help("add a closing '\"' to end the string")
+                        .at_eof(),
+                );
+                return;
+            }
+
+            match self.advance() {
+                '"' => break,
+                '$' if self.peek() == '{' => {
+                    self.advance();
+                    let start = self.span_from(self.current - 2);
+                    self.interpolations.push((0, start));
+                    self.add_token(TokenType::INTERPOLATION, Some(Literal::Str(value)));
+                    return;
+                }
+                '\\' => self.escape(&mut value, reporter),
+                '\n' => {
+                    self.new_line();
+                    value.push('\n');
+                }
+                c => value.push(c),
             }
-            self.advance();
         }
 
+        self.add_token(TokenType::STRING, Some(Literal::Str(value)));
+    }
+
+    /// Handles the escape sequence after a backslash in a string, appending
+    /// the character it stands for to `value`. A backslash at the end of a
+    /// line continues the string on the next line, skipping the newline and
+    /// the next line's indentation.
+    fn escape<T>(&mut self, value: &mut String, reporter: &mut T)
+    where
+        T: FnMut(Diagnostic),
+    {
+        let backslash = self.current - 1;
         if self.is_at_end() {
-            reporter(self.line, "Unterminated string.");
+            // Reported as an unterminated string by the caller.
             return;
         }
 
+        match self.advance() {
+            'n' => value.push('\n'),
+            't' => value.push('\t'),
+            'r' => value.push('\r'),
+            '0' => value.push('\0'),
+            '"' => value.push('"'),
+            '\\' => value.push('\\'),
+            '$' => value.push('$'),
+            'u' => self.unicode_escape(backslash, value, reporter),
+            '\n' => self.continue_line(),
+            '\r' if self.peek() == '\n' => {
+                self.advance();
+                self.continue_line();
+            }
+            c => reporter(
+                Diagnostic::error(
+                    SCAN_ERROR,
+                    format!("Unknown escape sequence '\\{}'.", c.escape_debug()),
+                    self.span_from(backslash),
+                )
+                .with_note("valid escapes are \\n, \\t, \\r, \\0, \\\", \\\\, \\$ and \\u{...}")
+                .with_help("write '\\\\' for a literal backslash"),
+            ),
+        }
+    }
+
+    /// `\u{...}`: one to six hex digits naming a Unicode scalar value.
+    fn unicode_escape<T>(&mut self, backslash: usize, value: &mut String, reporter: &mut T)
+    where
+        T: FnMut(Diagnostic),
+    {
+        let mut digits = String::new();
+        let closed = self.match_next('{') && {
+            while self.peek().is_ascii_hexdigit() {
+                digits.push(self.advance());
+            }
+            self.match_next('}')
+        };
+
+        let c = u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32);
+        match c {
+            Some(c) if closed && digits.len() <= 6 => value.push(c),
+            _ => {
+                let message = if closed && !digits.is_empty() {
+                    format!("'{digits}' is not a valid Unicode code point.")
+                } else {
+                    "Invalid Unicode escape.".to_string()
+                };
+                reporter(
+                    Diagnostic::error(SCAN_ERROR, message, self.span_from(backslash)).with_help(
+                        "write Unicode escapes as \\u{...} with 1 to 6 hex digits, e.g. \\u{1F600}",
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Skips the newline after a line-continuation backslash along with the
+    /// indentation of the following line.
+    fn continue_line(&mut self) {
+        self.new_line();
+        while matches!(self.peek(), ' ' | '\t') {
+            self.advance();
+        }
+    }
+
+    /// Span from byte `start` on the current line up to `current`.
+    fn span_from(&self, start: usize) -> Span {
+        let column = self.source[self.line_start..start].chars().count() + 1;
+        Span::new(start, self.current, self.line, column)
+    }
+
+    /// A triple-quoted string. Its text is kept exactly as written: escapes
+    /// are not processed and it may contain newlines and lone quotes.
+    fn raw_string<T>(&mut self, reporter: &mut T)
+    where
+        T: FnMut(Diagnostic),
+    {
+        // The opening quote has been consumed; consume the other two.
         self.advance();
+        self.advance();
+        let content_start = self.current;
+
+        while !self.source[self.current..].starts_with("\"\"\"") {
+            if self.is_at_end() {
+                reporter(
+                    Diagnostic::error(SCAN_ERROR, "Unterminated string.", self.token_span())
+                        .with_help("add a closing '\"\"\"' to end the string")
+                        .at_eof(),
+                );
+                return;
+            }
+            if self.advance() == '\n' {
+                self.new_line();
+            }
+        }
 
-        let value = &self.source[self.start + 1..self.current - 1];
-        let trimmed_and_normalized = value
-            .replace("\\", "")
-            .split_whitespace()
-            .collect::<Vec<&str>>()
-            .join(" ");
-        self.add_token(
-            TokenType::STRING,
-            Some(Literal::Str(trimmed_and_normalized)),
-        );
+        let value = self.source[content_start..self.current].to_string();
+        for _ in 0..3 {
+            self.advance();
+        }
+        self.add_token(TokenType::STRING, Some(Literal::Str(value)));
     }
 
     pub fn scan_tokens<T>(&mut self, reporter: &mut T) -> Vec<Token>
     where
-        T: FnMut(usize, &str),
+        T: FnMut(Diagnostic),
     {
         while !self.is_at_end() {
-            self.start = self.current;
+            self.mark_start();
             self.scan_token(reporter);
         }
 
-        self.start = self.current;
+        for (_, start) in std::mem::take(&mut self.interpolations) {
+            reporter(
+                Diagnostic::error(SCAN_ERROR, "Unterminated string interpolation.", start)
+                    .with_help("close the interpolation with '}' and the string with '\"'")
+                    .at_eof(),
+            );
+        }
+
+        // Every token stream ends in exactly one EOF token. Its offsets are
+        // the end of the source, but its line and column are just past the
+        // last non-blank character, so that "expected ';'" and the like are
+        // reported on a line that exists rather than after the final newline.
+        let content = self.source.trim_end();
+        let last_line = &content[content.rfind('\n').map_or(0, |i| i + 1)..];
+        let trailing_newlines = self.source[content.len()..].matches('\n').count();
+        self.tokens.push(Token {
+            token_type: TokenType::EOF,
+            lexeme: String::new(),
+            literal: Literal::Nil,
+            span: Span::new(
+                self.source.len(),
+                self.source.len(),
+                self.line - trailing_newlines,
+                last_line.chars().count() + 1,
+            ),
+        });
         std::mem::take(&mut self.tokens)
     }
 }
+
+/// Why a number literal is malformed, reported against the whole literal.
+struct NumberError {
+    message: String,
+    help: String,
+}
+
+fn number_error(message: impl Into<String>, help: impl Into<String>) -> NumberError {
+    NumberError {
+        message: message.into(),
+        help: help.into(),
+    }
+}
+
+fn invalid_number() -> NumberError {
+    number_error("Invalid number literal.", "write numbers like 42 or 1.5")
+}
+
+fn radix_example(radix: u32) -> &'static str {
+    match radix {
+        16 => "0xFF",
+        2 => "0b1010",
+        _ => "0o755",
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
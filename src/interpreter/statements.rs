@@ -0,0 +1,123 @@
+use crate::interpreter::expressions::Expr;
+use crate::interpreter::scanner::Token;
+use crate::interpreter::span::Span;
+use std::rc::Rc;
+
+pub trait Visitor<T> {
+    fn visit_expression_stmt(&mut self, stmt: &ExpressionStmt) -> T;
+    fn visit_print_stmt(&mut self, stmt: &PrintStmt) -> T;
+    fn visit_var_stmt(&mut self, stmt: &VarStmt) -> T;
+    fn visit_block_stmt(&mut self, stmt: &BlockStmt) -> T;
+    fn visit_if_stmt(&mut self, stmt: &IfStmt) -> T;
+    fn visit_while_stmt(&mut self, stmt: &WhileStmt) -> T;
+    fn visit_function_stmt(&mut self, stmt: &Rc<FunctionStmt>) -> T;
+    fn visit_return_stmt(&mut self, stmt: &ReturnStmt) -> T;
+    fn visit_class_stmt(&mut self, stmt: &ClassStmt) -> T;
+}
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    Expression(ExpressionStmt),
+    Print(PrintStmt),
+    Var(VarStmt),
+    Block(BlockStmt),
+    If(Box<IfStmt>),
+    While(Box<WhileStmt>),
+    Function(Rc<FunctionStmt>),
+    Return(ReturnStmt),
+    Class(ClassStmt),
+}
+
+impl Stmt {
+    pub fn accept<T>(&self, visitor: &mut dyn Visitor<T>) -> T {
+        match self {
+            Stmt::Expression(stmt) => visitor.visit_expression_stmt(stmt),
+            Stmt::Print(stmt) => visitor.visit_print_stmt(stmt),
+            Stmt::Var(stmt) => visitor.visit_var_stmt(stmt),
+            Stmt::Block(stmt) => visitor.visit_block_stmt(stmt),
+            Stmt::If(stmt) => visitor.visit_if_stmt(stmt),
+            Stmt::While(stmt) => visitor.visit_while_stmt(stmt),
+            Stmt::Function(stmt) => visitor.visit_function_stmt(stmt),
+            Stmt::Return(stmt) => visitor.visit_return_stmt(stmt),
+            Stmt::Class(stmt) => visitor.visit_class_stmt(stmt),
+        }
+    }
+
+    /// The region of source this statement was parsed from.
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Expression(stmt) => stmt.span,
+            Stmt::Print(stmt) => stmt.span,
+            Stmt::Var(stmt) => stmt.span,
+            Stmt::Block(stmt) => stmt.span,
+            Stmt::If(stmt) => stmt.span,
+            Stmt::While(stmt) => stmt.span,
+            Stmt::Function(stmt) => stmt.span,
+            Stmt::Return(stmt) => stmt.span,
+            Stmt::Class(stmt) => stmt.span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExpressionStmt {
+    pub expression: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrintStmt {
+    pub expression: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct VarStmt {
+    pub name: Token,
+    pub initializer: Option<Expr>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockStmt {
+    pub statements: Vec<Stmt>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct IfStmt {
+    pub condition: Expr,
+    pub then_branch: Stmt,
+    pub else_branch: Option<Stmt>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct WhileStmt {
+    pub condition: Expr,
+    pub body: Stmt,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionStmt {
+    pub name: Token,
+    pub params: Vec<Token>,
+    pub body: Vec<Stmt>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReturnStmt {
+    pub keyword: Token,
+    pub value: Option<Expr>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassStmt {
+    pub name: Token,
+    pub superclass: Option<Expr>,
+    pub methods: Vec<Rc<FunctionStmt>>,
+    pub span: Span,
+}
@@ -14,6 +14,7 @@ pub enum TokenType {
     MINUS,
     PLUS,
     SEMICOLON,
+    PERCENT,
     SLASH,
     STAR,
 
@@ -26,10 +27,15 @@ pub enum TokenType {
     GREATER_EQUAL,
     LESS,
     LESS_EQUAL,
+    /// `~/`, floor division. Not `//`, which already starts a line comment.
+    TILDE_SLASH,
 
     // Literals.
     IDENTIFIER,
     STRING,
+    /// The part of a string literal before a `${` interpolation, or between
+    /// two interpolations. The string's final part is a STRING token.
+    INTERPOLATION,
     NUMBER,
 
     // Keywords.
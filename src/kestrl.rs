@@ -1,62 +1,310 @@
-use crate::interpreter::{interpreter::Interpreter, parser::Parser, scanner::Scanner};
+use crate::interpreter::{
+    callable::Arity,
+    convert::FromArgs,
+    diagnostics::Diagnostic,
+    interpreter::{Interpreter, RuntimeError, Value},
+    parser::Parser,
+    resolver::Resolver,
+    scanner::{Scanner, Token},
+    statements::Stmt,
+};
+use std::{
+    error::Error,
+    fmt,
+    io::{self, IsTerminal, Write},
+};
 
+/// Why running some Kestrl source failed. Compile-time phases can report
+/// several errors at once; a runtime error stops execution at the first one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KestrlError {
+    Scan(Vec<Diagnostic>),
+    Parse(Vec<Diagnostic>),
+    Resolve(Vec<Diagnostic>),
+    Runtime(Box<Diagnostic>),
+}
+
+impl KestrlError {
+    /// Every error reported, each carrying the span it points at. Only a
+    /// runtime error from [`Kestrl::call`] itself can be without one.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        match self {
+            KestrlError::Scan(diagnostics)
+            | KestrlError::Parse(diagnostics)
+            | KestrlError::Resolve(diagnostics) => diagnostics,
+            KestrlError::Runtime(diagnostic) => std::slice::from_ref(&**diagnostic),
+        }
+    }
+
+    /// Renders every diagnostic against the source it came from, see
+    /// [`Diagnostic::render`].
+    pub fn render(&self, source: &str, first_line: usize, color: bool) -> String {
+        self.diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.render(source, first_line, color))
+            .collect()
+    }
+}
+
+impl fmt::Display for KestrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phase = match self {
+            KestrlError::Scan(_) => "scan",
+            KestrlError::Parse(_) => "parse",
+            KestrlError::Resolve(_) => "resolve",
+            KestrlError::Runtime(_) => "runtime",
+        };
+        let diagnostics = self.diagnostics();
+        match diagnostics.first() {
+            Some(Diagnostic {
+                span: Some(span),
+                message,
+                ..
+            }) => write!(
+                f,
+                "{phase} error at {}:{}: {message}",
+                span.line, span.column
+            )?,
+            Some(first) => write!(f, "{phase} error: {}", first.message)?,
+            None => write!(f, "{phase} error")?,
+        }
+        if diagnostics.len() > 1 {
+            write!(f, " (and {} more)", diagnostics.len() - 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for KestrlError {}
+
+/// Stack size for [`with_stack`]. The parser, resolver and interpreter all
+/// recurse, and their nesting and call depth limits are sized for this much
+/// stack in a debug build.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Runs `run` on a new thread with a [`STACK_SIZE`] stack and returns its
+/// result. Hosts should run Kestrl this way, or on a thread at least as
+/// large: deep but legal programs can overflow an ordinary thread's stack.
+pub fn with_stack<T: Send>(run: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, run)
+            .expect("failed to spawn the interpreter thread")
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// A Kestrl session. The interpreter, and with it the global environment,
+/// lives as long as the session so that REPL inputs and successive `eval`
+/// calls can build on each other.
+///
+/// Program output goes to stdout unless redirected with
+/// [`Kestrl::set_output`]. Failures are returned as [`KestrlError`]s and only
+/// written out if the caller passes them to [`Kestrl::report`], which uses
+/// stderr unless redirected with [`Kestrl::set_error_output`].
 pub struct Kestrl<'a> {
     source: &'a str,
-    pub had_error: bool,
+    interpreter: Interpreter,
+    errors: Box<dyn Write>,
+    color: bool,
+    /// Every REPL input and loaded file run so far, with the session line it
+    /// starts on. A runtime error can come from a function defined in an
+    /// earlier input, so it is rendered against whichever one it points at.
+    inputs: Vec<(usize, String)>,
 }
 
 impl<'a> Kestrl<'a> {
     pub fn new(source: &'a str) -> Self {
         Kestrl {
             source,
-            had_error: false,
+            interpreter: Interpreter::new(),
+            errors: Box::new(io::stderr()),
+            color: io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            inputs: Vec::new(),
         }
     }
 
-    pub fn run(&mut self) {
-        let mut scanner = Scanner::new(self.source);
-        let had_error_flag = &mut self.had_error;
-        let mut reporter = |line: usize, message: &str| {
-            *had_error_flag = true;
-            eprintln!("[line {line}] Error: {message}");
-        };
+    /// Sends the output of `print`, and values echoed by the REPL, to
+    /// `output` instead of stdout.
+    pub fn set_output(&mut self, output: impl Write + 'static) {
+        self.interpreter.set_output(output);
+    }
+
+    /// Sends reported errors to `errors` instead of stderr. Errors written to
+    /// a custom sink are never coloured.
+    pub fn set_error_output(&mut self, errors: impl Write + 'static) {
+        self.errors = Box::new(errors);
+        self.color = false;
+    }
+
+    /// Runs the source the session was created with. The result is the value
+    /// of the final statement if it is an expression statement, and `nil`
+    /// otherwise.
+    pub fn run(&mut self) -> Result<Value, KestrlError> {
+        self.run_source(self.source, 1)
+    }
+
+    /// Runs `source` against the session's state, returning the same kind of
+    /// result as [`Kestrl::run`].
+    pub fn eval(&mut self, source: &str) -> Result<Value, KestrlError> {
+        self.run_source(source, 1)
+    }
 
-        let tokens = scanner.scan_tokens(&mut reporter);
+    /// Runs one REPL input against the session's state. `line_number` is the
+    /// session line the input starts on and is used in error positions.
+    /// Errors from earlier inputs do not prevent this one from running.
+    /// The values of bare expression statements are echoed back.
+    pub fn run_line(
+        &mut self,
+        line_source: &str,
+        line_number: usize,
+    ) -> Result<Value, KestrlError> {
+        self.inputs.push((line_number, line_source.to_string()));
+        self.interpreter.echo = true;
+        let result = self.run_source(line_source, line_number);
+        self.interpreter.echo = false;
+        result
+    }
+
+    /// Runs a whole file in a REPL session, as `:load` does. The file's lines
+    /// are numbered as session lines starting at `line_number`, and nothing
+    /// is echoed.
+    pub fn load(&mut self, source: &str, line_number: usize) -> Result<Value, KestrlError> {
+        self.inputs.push((line_number, source.to_string()));
+        self.run_source(source, line_number)
+    }
+
+    /// Discards every global defined so far and starts from a fresh
+    /// interpreter.
+    pub fn reset(&mut self) {
+        self.interpreter.reset();
+    }
+
+    /// Makes `args` available to scripts through the `argc()` and `argv(n)`
+    /// builtins. `argv` returns `nil` for an index that is out of range.
+    pub fn set_args(&mut self, args: Vec<String>) {
+        let count = args.len() as i64;
+        self.define_fn("argc", move |()| Ok(count));
+        self.define_fn("argv", move |(n,): (i64,)| {
+            Ok(usize::try_from(n).ok().and_then(|n| args.get(n)).cloned())
+        });
+    }
+
+    /// See [`Interpreter::define_native`].
+    pub fn define_native<F>(&mut self, name: &str, arity: impl Into<Arity>, function: F)
+    where
+        F: Fn(&[Value]) -> Result<Value, RuntimeError> + 'static,
+    {
+        self.interpreter.define_native(name, arity, function);
+    }
+
+    /// See [`Interpreter::define_fn`].
+    pub fn define_fn<A, R, F>(&mut self, name: &str, function: F)
+    where
+        A: FromArgs,
+        R: Into<Value>,
+        F: Fn(A) -> Result<R, RuntimeError> + 'static,
+    {
+        self.interpreter.define_fn(name, function);
+    }
+
+    /// The value of the global `name`, if a script has defined it.
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.interpreter.get_global(name)
+    }
 
-        if *had_error_flag {
-            return;
+    /// Defines the global `name` for scripts run afterwards, replacing any
+    /// existing value.
+    pub fn set_global(&mut self, name: &str, value: impl Into<Value>) {
+        self.interpreter.set_global(name, value);
+    }
+
+    /// Calls a Kestrl function or class, typically one fetched with
+    /// [`Kestrl::get_global`], with arguments supplied from Rust. Errors
+    /// about the call itself, such as a wrong argument count, have no
+    /// source position.
+    pub fn call(&mut self, callee: &Value, arguments: Vec<Value>) -> Result<Value, KestrlError> {
+        self.interpreter
+            .call(callee, arguments)
+            .map_err(|error| KestrlError::Runtime(Box::new(Diagnostic::from(error))))
+    }
+
+    /// The session's global variables, sorted by name.
+    pub fn globals(&self) -> Vec<(String, Value)> {
+        self.interpreter.globals.borrow().bindings()
+    }
+
+    /// Scans `source` into tokens. `first_line` is the line number the
+    /// source starts at.
+    pub fn scan(source: &str, first_line: usize) -> Result<Vec<Token>, KestrlError> {
+        let mut diagnostics = Vec::new();
+        let mut reporter = |diagnostic: Diagnostic| diagnostics.push(diagnostic);
+        let tokens = Scanner::with_line(source, first_line).scan_tokens(&mut reporter);
+        if !diagnostics.is_empty() {
+            return Err(KestrlError::Scan(diagnostics));
         }
-        let mut parser = Parser::new(tokens);
-        let expression = parser.parse();
-        let mut interpreter = Interpreter::new();
-        interpreter.interpret(&expression);
-    }
-
-    pub fn run_line(&mut self, line_source: &str, _line_number: usize) {
-        let mut scanner = Scanner::new(line_source);
-        let had_error_flag = &mut self.had_error;
-        let mut reporter = |line: usize, message: &str| {
-            *had_error_flag = true;
-            eprintln!("[line {line}] Error: {message}");
-        };
+        Ok(tokens)
+    }
+
+    /// Scans and parses `source`.
+    pub fn parse(source: &str, first_line: usize) -> Result<Vec<Stmt>, KestrlError> {
+        let tokens = Self::scan(source, first_line)?;
+        Parser::new(tokens).parse().map_err(|errors| {
+            KestrlError::Parse(errors.into_iter().map(Diagnostic::from).collect())
+        })
+    }
 
-        let tokens = scanner.scan_tokens(&mut reporter);
+    /// Scans, parses and resolves `source` without running it.
+    pub fn check(source: &str, first_line: usize) -> Result<Vec<Stmt>, KestrlError> {
+        let statements = Self::parse(source, first_line)?;
 
-        if *had_error_flag {
-            return;
+        let mut diagnostics = Vec::new();
+        let mut reporter = |diagnostic: Diagnostic| diagnostics.push(diagnostic);
+        Resolver::new(&mut reporter).resolve(&statements);
+        if !diagnostics.is_empty() {
+            return Err(KestrlError::Resolve(diagnostics));
         }
-        let mut parser = Parser::new(tokens);
-        let expression = parser.parse();
-        let mut interpreter = Interpreter::new();
-        interpreter.interpret(&expression);
+        Ok(statements)
     }
 
-    pub fn error(&mut self, line: usize, message: &str) {
-        self.had_error = true;
-        self.report(line, "", message);
+    fn run_source(&mut self, source: &str, first_line: usize) -> Result<Value, KestrlError> {
+        let statements = Self::check(source, first_line)?;
+        self.interpreter
+            .interpret(&statements)
+            .map_err(|error| KestrlError::Runtime(Box::new(Diagnostic::from(error))))
+    }
+
+    /// Writes `error` to the error sink, rendered against the source it came
+    /// from. Colour is used when writing to a terminal and `NO_COLOR` is not
+    /// set.
+    ///
+    /// Runtime errors that point into an earlier REPL input or loaded file are
+    /// rendered against that instead.
+    pub fn report(&mut self, error: &KestrlError, source: &str, first_line: usize) {
+        let (source, first_line) = match error {
+            KestrlError::Runtime(diagnostic) => diagnostic
+                .span
+                .and_then(|span| self.input_containing(span.line))
+                .unwrap_or((source, first_line)),
+            _ => (source, first_line),
+        };
+        let rendered = error.render(source, first_line, self.color);
+        // There is nowhere left to report a failure to report.
+        let _ = self
+            .errors
+            .write_all(rendered.as_bytes())
+            .and_then(|_| self.errors.flush());
     }
 
-    pub fn report(&self, line: usize, where_: &str, message: &str) {
-        eprintln!("[line {line}] Error{where_}: {message}");
+    /// The most recent input covering session line `line`, and the line it
+    /// starts on.
+    fn input_containing(&self, line: usize) -> Option<(&str, usize)> {
+        self.inputs
+            .iter()
+            .rev()
+            .find(|(first, text)| (*first..*first + text.split('\n').count()).contains(&line))
+            .map(|(first, text)| (text.as_str(), *first))
     }
 }
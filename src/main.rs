@@ -1,49 +1,295 @@
-use kestrl::kestrl::Kestrl;
+use kestrl::interpreter::ast_printer::AstPrinter;
+use kestrl::{
+    kestrl::{Kestrl, KestrlError, with_stack},
+    repl::{
+        self, Command,
+        editor::{LineEditor, ReadLine},
+        history::History,
+        terminal::StdTerminal,
+    },
+};
 use std::env;
-use std::io::{self, BufRead, BufReader, Write};
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() > 2 {
-        eprintln!("Usage: kestrl -- {}", args[1]);
-        std::process::exit(1);
-    } else if args.len() == 2 {
-        if let Err(e) = run_file(&args[1]) {
-            eprintln!("Error running file {}: {}", &args[1], e);
-            std::process::exit(1);
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: kestrl [command]
+
+Commands:
+  repl                   start the interactive prompt (the default)
+  run <file> [args...]   run a script, passing it the remaining arguments
+  check <file>           parse and resolve a script without running it
+  tokens <file>          print the tokens of a script
+  ast <file>             print the syntax tree of a script
+  <file> [args...]       same as `run <file> [args...]`
+
+Options:
+  -e <code> [args...]    run <code> instead of a script file
+  -h, --help             print this message
+
+A <file> of `-` reads the script from standard input.";
+
+// Exit codes, following the BSD sysexits convention.
+const EX_USAGE: u8 = 64;
+const EX_DATAERR: u8 = 65;
+const EX_NOINPUT: u8 = 66;
+const EX_SOFTWARE: u8 = 70;
+const EX_IOERR: u8 = 74;
+
+/// Where a script's source comes from.
+enum Input {
+    File(String),
+    Stdin,
+    Inline(String),
+}
+
+enum Cli {
+    Help,
+    Repl,
+    Run { input: Input, args: Vec<String> },
+    Check(Input),
+    Tokens(Input),
+    Ast(Input),
+}
+
+fn main() -> ExitCode {
+    with_stack(run_cli)
+}
+
+fn run_cli() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let cli = match parse_args(&args) {
+        Ok(cli) => cli,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(EX_USAGE);
         }
+    };
+
+    match cli {
+        Cli::Help => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Cli::Repl => match run_repl() {
+            Ok(_) => {
+                eprintln!("REPL ended successfully");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Error in REPL: {e}");
+                ExitCode::from(EX_IOERR)
+            }
+        },
+        Cli::Run { input, args } => with_source(&input, |source| {
+            let mut kestrl = Kestrl::new(source);
+            kestrl.set_args(args);
+            match kestrl.run() {
+                Ok(_) => ExitCode::SUCCESS,
+                Err(error) => {
+                    kestrl.report(&error, source, 1);
+                    exit_code(&error)
+                }
+            }
+        }),
+        Cli::Check(input) => with_source(&input, |source| {
+            reported(Kestrl::check(source, 1).map(|_| ()), source)
+        }),
+        Cli::Tokens(input) => with_source(&input, |source| {
+            let tokens = Kestrl::scan(source, 1).map(|tokens| {
+                for token in tokens {
+                    println!("{}:{} {token}", token.span.line, token.span.column);
+                }
+            });
+            reported(tokens, source)
+        }),
+        Cli::Ast(input) => with_source(&input, |source| {
+            let statements = Kestrl::parse(source, 1).map(|statements| {
+                println!("{}", AstPrinter.print_program(&statements));
+            });
+            reported(statements, source)
+        }),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Cli, String> {
+    let Some(first) = args.first() else {
+        return Ok(Cli::Repl);
+    };
+    let rest = &args[1..];
+
+    let single_input = |command: &str| match rest {
+        [file] => Ok(input_for(file)),
+        [] => Err(format!("`{command}` needs a file")),
+        _ => Err(format!("`{command}` takes a single file")),
+    };
+
+    match first.as_str() {
+        "-h" | "--help" => Ok(Cli::Help),
+        "repl" if rest.is_empty() => Ok(Cli::Repl),
+        "repl" => Err("`repl` takes no arguments".to_string()),
+        "check" => single_input("check").map(Cli::Check),
+        "tokens" => single_input("tokens").map(Cli::Tokens),
+        "ast" => single_input("ast").map(Cli::Ast),
+        "run" => match rest.split_first() {
+            Some((file, _)) => Ok(Cli::Run {
+                input: input_for(file),
+                args: rest.to_vec(),
+            }),
+            None => Err("`run` needs a file".to_string()),
+        },
+        "-e" => match rest.first() {
+            Some(code) => Ok(Cli::Run {
+                input: Input::Inline(code.clone()),
+                args: std::iter::once("-e".to_string())
+                    .chain(rest[1..].iter().cloned())
+                    .collect(),
+            }),
+            None => Err("`-e` needs some code to run".to_string()),
+        },
+        option if option.starts_with('-') && option != "-" => {
+            Err(format!("unknown option `{option}`"))
+        }
+        file => Ok(Cli::Run {
+            input: input_for(file),
+            args: args.to_vec(),
+        }),
+    }
+}
+
+fn input_for(file: &str) -> Input {
+    if file == "-" {
+        Input::Stdin
     } else {
-        match run_repl() {
-            Ok(_) => eprintln!("REPL ended successfully"),
-            Err(e) => eprintln!("Error in REPL: {e}"),
+        Input::File(file.to_string())
+    }
+}
+
+/// Reads the script named by `input` and hands it to `run`, or reports why
+/// it could not be read.
+fn with_source(input: &Input, run: impl FnOnce(&str) -> ExitCode) -> ExitCode {
+    let source = match input {
+        Input::File(path) => std::fs::read_to_string(path).map_err(|e| (path.as_str(), e)),
+        Input::Stdin => {
+            let mut source = String::new();
+            io::stdin()
+                .read_to_string(&mut source)
+                .map(|_| source)
+                .map_err(|e| ("standard input", e))
+        }
+        Input::Inline(code) => Ok(code.clone()),
+    };
+
+    match source {
+        Ok(source) => run(&source),
+        Err((name, e)) => {
+            eprintln!("error: could not read {name}: {e}");
+            ExitCode::from(EX_NOINPUT)
         }
     }
 }
 
-fn run_file(file_path: &str) -> io::Result<()> {
-    let contents = std::fs::read_to_string(file_path)?;
-    let mut kestrl = Kestrl::new(&contents);
-    kestrl.run();
+fn exit_code(error: &KestrlError) -> ExitCode {
+    match error {
+        KestrlError::Runtime(_) => ExitCode::from(EX_SOFTWARE),
+        _ => ExitCode::from(EX_DATAERR),
+    }
+}
 
-    Ok(())
+/// Reports the error, if any, from a command that does not run the script.
+fn reported(result: Result<(), KestrlError>, source: &str) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            Kestrl::new(source).report(&error, source, 1);
+            exit_code(&error)
+        }
+    }
 }
 
 fn run_repl() -> io::Result<()> {
-    let stdin = io::stdin();
-    let mut reader = BufReader::new(stdin.lock());
-    let mut line_cnt = 1;
     let mut kestrl = Kestrl::new("");
+    match StdTerminal::new() {
+        Some(terminal) => {
+            let history = match History::default_path() {
+                Some(path) => History::with_file(path).unwrap_or_default(),
+                None => History::new(),
+            };
+            let mut editor = LineEditor::new(terminal, history);
+            repl_loop(&mut kestrl, |kestrl, prompt| {
+                editor.set_completions(repl::completion_words(kestrl));
+                editor.read_line(prompt)
+            })
+        }
+        None => {
+            let stdin = io::stdin();
+            let mut reader = BufReader::new(stdin.lock());
+            repl_loop(&mut kestrl, |_, prompt| {
+                print!("{prompt}");
+                io::stdout().flush()?;
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 {
+                    return Ok(ReadLine::Eof);
+                }
+                Ok(ReadLine::Line(line))
+            })
+        }
+    }
+}
+
+/// Reads, evaluates and prints until the input ends. `read_line` shows the
+/// prompt and returns the next line of input.
+fn repl_loop<F>(kestrl: &mut Kestrl, mut read_line: F) -> io::Result<()>
+where
+    F: FnMut(&Kestrl, &str) -> io::Result<ReadLine>,
+{
+    let mut line_cnt = 1;
+    let mut input = String::new();
 
     loop {
-        print!(">>>");
-        io::stdout().flush()?;
-        let mut line = String::new();
-        if reader.read_line(&mut line)? == 0 || line.trim() == "exit" {
+        let prompt = if input.is_empty() { ">>>" } else { "..." };
+        let mut line = match read_line(kestrl, prompt)? {
+            ReadLine::Line(line) => line,
+            ReadLine::Interrupted => {
+                input.clear();
+                continue;
+            }
+            ReadLine::Eof => break,
+        };
+        if !line.ends_with('\n') {
+            line.push('\n');
+        }
+        if input.is_empty() && line.trim() == "exit" {
             break;
         }
-        kestrl.run_line(&line, line_cnt);
 
-        line_cnt += 1;
+        if input.is_empty() && line.trim_start().starts_with(':') {
+            line_cnt += match Command::parse(&line) {
+                Ok(command) => command.execute(kestrl, line_cnt),
+                Err(message) => {
+                    println!("{message}");
+                    1
+                }
+            };
+            continue;
+        }
+
+        // A blank line submits whatever has been typed so far, so a stray
+        // unclosed brace can always be escaped.
+        input.push_str(&line);
+        if !line.trim().is_empty() && !repl::is_complete(&input) {
+            continue;
+        }
+
+        // The blank lines that submitted the input are not part of it, so an
+        // error at the end of the input is shown on its last line.
+        let code = input.trim_end();
+        if let Err(error) = kestrl.run_line(code, line_cnt) {
+            kestrl.report(&error, code, line_cnt);
+        }
+
+        line_cnt += input.lines().count().max(1);
+        input.clear();
     }
     Ok(())
 }
@@ -0,0 +1,299 @@
+use crate::repl::{
+    history::History,
+    terminal::{Key, Terminal},
+};
+use std::io;
+
+/// The outcome of reading one line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadLine {
+    Line(String),
+    /// Ctrl-C was pressed; the line typed so far was discarded.
+    Interrupted,
+    /// Ctrl-D on an empty line, or the input ended.
+    Eof,
+}
+
+/// An Emacs-style line editor: cursor movement, history recall with the
+/// arrow keys, Ctrl-R reverse search and tab completion.
+pub struct LineEditor<T: Terminal> {
+    terminal: T,
+    history: History,
+    completions: Vec<String>,
+}
+
+/// The line being edited and the cursor position within it, in characters.
+#[derive(Default)]
+struct Line {
+    chars: Vec<char>,
+    cursor: usize,
+}
+
+impl Line {
+    fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    fn set(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.cursor = self.chars.len();
+    }
+
+    fn insert(&mut self, text: &str) {
+        for c in text.chars() {
+            self.chars.insert(self.cursor, c);
+            self.cursor += 1;
+        }
+    }
+
+    /// Start of the identifier-like word ending at the cursor.
+    fn word_start(&self) -> usize {
+        self.chars[..self.cursor]
+            .iter()
+            .rposition(|c| !(c.is_alphanumeric() || *c == '_'))
+            .map_or(0, |index| index + 1)
+    }
+}
+
+/// How a reverse search ended.
+enum SearchEnd {
+    /// Enter was pressed on a match, which should be submitted as is.
+    Submit,
+    /// The search was accepted or cancelled and editing continues.
+    Edit,
+}
+
+impl<T: Terminal> LineEditor<T> {
+    pub fn new(terminal: T, history: History) -> Self {
+        LineEditor {
+            terminal,
+            history,
+            completions: Vec::new(),
+        }
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn terminal(&self) -> &T {
+        &self.terminal
+    }
+
+    /// Sets the words offered by tab completion.
+    pub fn set_completions(&mut self, words: Vec<String>) {
+        self.completions = words;
+    }
+
+    /// Reads one line, showing `prompt` before it. Submitted lines are added
+    /// to the history.
+    pub fn read_line(&mut self, prompt: &str) -> io::Result<ReadLine> {
+        self.terminal.begin_line()?;
+        let result = self.edit(prompt);
+        self.terminal.end_line()?;
+
+        if let Ok(ReadLine::Line(line)) = &result
+            && let Err(error) = self.history.add(line)
+        {
+            // Not worth ending the session over. The history stops saving
+            // after a failure, so this is only shown once.
+            self.terminal
+                .write(&format!("warning: history will not be saved: {error}\r\n"))?;
+        }
+        result
+    }
+
+    fn edit(&mut self, prompt: &str) -> io::Result<ReadLine> {
+        let mut line = Line::default();
+        // Position in the history while browsing with the arrow keys;
+        // `history.len()` is the line being typed, kept in `draft`.
+        let mut history_index = self.history.len();
+        let mut draft = String::new();
+
+        self.refresh(prompt, &line)?;
+        loop {
+            let Some(key) = self.terminal.read_key()? else {
+                self.terminal.write("\r\n")?;
+                return Ok(if line.chars.is_empty() {
+                    ReadLine::Eof
+                } else {
+                    ReadLine::Line(line.text())
+                });
+            };
+
+            match key {
+                Key::Enter => {
+                    self.terminal.write("\r\n")?;
+                    return Ok(ReadLine::Line(line.text()));
+                }
+                Key::Ctrl('c') => {
+                    self.terminal.write("^C\r\n")?;
+                    return Ok(ReadLine::Interrupted);
+                }
+                Key::Ctrl('d') if line.chars.is_empty() => {
+                    self.terminal.write("\r\n")?;
+                    return Ok(ReadLine::Eof);
+                }
+                Key::Char(c) => line.insert(&c.to_string()),
+                Key::Backspace if line.cursor > 0 => {
+                    line.cursor -= 1;
+                    line.chars.remove(line.cursor);
+                }
+                Key::Delete | Key::Ctrl('d') if line.cursor < line.chars.len() => {
+                    line.chars.remove(line.cursor);
+                }
+                Key::Left | Key::Ctrl('b') => line.cursor = line.cursor.saturating_sub(1),
+                Key::Right | Key::Ctrl('f') => {
+                    line.cursor = (line.cursor + 1).min(line.chars.len());
+                }
+                Key::Home | Key::Ctrl('a') => line.cursor = 0,
+                Key::End | Key::Ctrl('e') => line.cursor = line.chars.len(),
+                Key::Ctrl('k') => line.chars.truncate(line.cursor),
+                Key::Ctrl('u') => {
+                    line.chars.drain(..line.cursor);
+                    line.cursor = 0;
+                }
+                Key::Up | Key::Ctrl('p') if history_index > 0 => {
+                    if history_index == self.history.len() {
+                        draft = line.text();
+                    }
+                    history_index -= 1;
+                    line.set(self.history.get(history_index).unwrap_or_default());
+                }
+                Key::Down | Key::Ctrl('n') if history_index < self.history.len() => {
+                    history_index += 1;
+                    match self.history.get(history_index) {
+                        Some(entry) => line.set(entry),
+                        None => line.set(&draft),
+                    }
+                }
+                Key::Tab => self.complete(prompt, &mut line)?,
+                Key::Ctrl('r') => {
+                    if let SearchEnd::Submit = self.reverse_search(&mut line)? {
+                        self.refresh(prompt, &line)?;
+                        self.terminal.write("\r\n")?;
+                        return Ok(ReadLine::Line(line.text()));
+                    }
+                }
+                _ => {}
+            }
+            self.refresh(prompt, &line)?;
+        }
+    }
+
+    /// Completes the word before the cursor. A unique match is inserted; with
+    /// several matches their common prefix is inserted, or, if that adds
+    /// nothing, the matches are listed.
+    fn complete(&mut self, prompt: &str, line: &mut Line) -> io::Result<()> {
+        let start = line.word_start();
+        let prefix: String = line.chars[start..line.cursor].iter().collect();
+        if prefix.is_empty() {
+            return Ok(());
+        }
+
+        let mut matches: Vec<&str> = self
+            .completions
+            .iter()
+            .map(String::as_str)
+            .filter(|word| word.starts_with(&prefix))
+            .collect();
+        matches.sort_unstable();
+        matches.dedup();
+
+        let Some(first) = matches.first() else {
+            return Ok(());
+        };
+        let common = matches.iter().fold(*first, |common, word| {
+            let len = common
+                .char_indices()
+                .zip(word.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(word.len()), |((index, _), _)| index);
+            &common[..len]
+        });
+
+        if common.len() > prefix.len() {
+            line.insert(&common[prefix.len()..]);
+        } else if matches.len() > 1 {
+            self.terminal
+                .write(&format!("\r\n{}\r\n", matches.join("  ")))?;
+            self.refresh(prompt, line)?;
+        }
+        Ok(())
+    }
+
+    /// Ctrl-R incremental search through the history, newest first. Typing
+    /// narrows the search, Ctrl-R again finds an older match, Enter submits
+    /// the match and Ctrl-G or Escape cancels. Any other key accepts the
+    /// match for further editing.
+    fn reverse_search(&mut self, line: &mut Line) -> io::Result<SearchEnd> {
+        let mut query = String::new();
+        let mut found: Option<usize> = None;
+        let mut failed = false;
+
+        loop {
+            let shown = found
+                .and_then(|index| self.history.get(index))
+                .unwrap_or("");
+            let label = if failed {
+                "failed reverse-i-search"
+            } else {
+                "reverse-i-search"
+            };
+            self.terminal
+                .write(&format!("\r({label})`{query}': {shown}\x1b[K"))?;
+
+            let key = self.terminal.read_key()?;
+            match key {
+                Some(Key::Char(c)) => {
+                    query.push(c);
+                    let from = found.map_or(self.history.len(), |index| index + 1);
+                    self.search(&query, from, &mut found, &mut failed);
+                }
+                Some(Key::Backspace) => {
+                    query.pop();
+                    let len = self.history.len();
+                    self.search(&query, len, &mut found, &mut failed);
+                }
+                Some(Key::Ctrl('r')) => {
+                    let from = found.unwrap_or(self.history.len());
+                    self.search(&query, from, &mut found, &mut failed);
+                }
+                Some(Key::Ctrl('g')) | Some(Key::Ctrl('c')) | Some(Key::Escape) => {
+                    return Ok(SearchEnd::Edit);
+                }
+                Some(key) => {
+                    if let Some(entry) = found.and_then(|index| self.history.get(index)) {
+                        line.set(entry);
+                    }
+                    return Ok(if key == Key::Enter {
+                        SearchEnd::Submit
+                    } else {
+                        SearchEnd::Edit
+                    });
+                }
+                None => return Ok(SearchEnd::Edit),
+            }
+        }
+    }
+
+    fn search(&self, query: &str, before: usize, found: &mut Option<usize>, failed: &mut bool) {
+        match self.history.search_back(query, before) {
+            Some(index) => {
+                *found = Some(index);
+                *failed = false;
+            }
+            None => *failed = true,
+        }
+    }
+
+    /// Redraws the prompt and line, then moves the cursor into place.
+    fn refresh(&mut self, prompt: &str, line: &Line) -> io::Result<()> {
+        let mut out = format!("\r{prompt}{}\x1b[K", line.text());
+        let behind = line.chars.len() - line.cursor;
+        if behind > 0 {
+            out.push_str(&format!("\x1b[{behind}D"));
+        }
+        self.terminal.write(&out)
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+pub const MAX_ENTRIES: usize = 1000;
+
+/// Lines previously entered at the REPL, oldest first. When backed by a file
+/// every new entry is appended to it so history survives between sessions,
+/// and the file is rewritten whenever old entries are dropped so that it
+/// stays within [`MAX_ENTRIES`] lines.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        History {
+            entries: Vec::new(),
+            path: None,
+        }
+    }
+
+    /// Loads history from `path`, which does not need to exist yet.
+    pub fn with_file(path: PathBuf) -> io::Result<Self> {
+        let entries = match fs::read_to_string(&path) {
+            Ok(contents) => contents.lines().map(str::to_string).collect(),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(error),
+        };
+        let mut history = History {
+            entries,
+            path: Some(path),
+        };
+        history.truncate();
+        Ok(history)
+    }
+
+    /// `~/.kestrl_history`, if the home directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".kestrl_history"))
+    }
+
+    /// Records a line, skipping blank lines and immediate repeats. If the
+    /// line cannot be saved it is still kept in memory, and the history
+    /// stops writing to its file for the rest of the session.
+    pub fn add(&mut self, line: &str) -> io::Result<()> {
+        let line = line.trim_end();
+        if line.trim().is_empty() || self.entries.last().is_some_and(|last| last == line) {
+            return Ok(());
+        }
+        self.entries.push(line.to_string());
+        let dropped = self.truncate();
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let saved = if dropped {
+            let mut contents = self.entries.join("\n");
+            contents.push('\n');
+            fs::write(path, contents)
+        } else {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{line}"))
+        };
+        if saved.is_err() {
+            self.path = None;
+        }
+        saved
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Index of the newest entry before `before` that contains `query`.
+    pub fn search_back(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    /// Drops the oldest entries beyond [`MAX_ENTRIES`], returning whether
+    /// there were any.
+    fn truncate(&mut self) -> bool {
+        if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+            return true;
+        }
+        false
+    }
+}
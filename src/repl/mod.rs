@@ -0,0 +1,176 @@
+pub mod editor;
+pub mod history;
+pub mod terminal;
+
+use crate::{
+    interpreter::{
+        ast_printer::AstPrinter,
+        diagnostics::Diagnostic,
+        parser::Parser,
+        scanner::{KEYWORDS, Scanner},
+        token_types::TokenType,
+    },
+    kestrl::Kestrl,
+};
+use std::time::Instant;
+
+/// Meta-commands understood by the REPL. They start with `:` and are handled
+/// before the input ever reaches the scanner, so they are not part of the
+/// language itself.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command<'a> {
+    Help,
+    Load(&'a str),
+    Reset,
+    Env,
+    Ast(&'a str),
+    Tokens(&'a str),
+    Time(&'a str),
+}
+
+const COMMANDS: &[(&str, &str)] = &[
+    (":help", "show this list of commands"),
+    (":load <file>", "run a file in the current session"),
+    (":reset", "forget every global defined so far"),
+    (":env", "list the global variables and their values"),
+    (
+        ":ast <code>",
+        "print the syntax tree of <code> without running it",
+    ),
+    (
+        ":tokens <code>",
+        "print the tokens of <code> without running it",
+    ),
+    (":time <code>", "run <code> and report how long it took"),
+];
+
+impl<'a> Command<'a> {
+    /// Parses a line starting with `:` into a command. The error is a message
+    /// for the user, such as a usage hint or an unknown command name.
+    pub fn parse(line: &'a str) -> Result<Self, String> {
+        let line = line.trim();
+        let (name, argument) = match line.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (line, ""),
+        };
+
+        let needs_argument = |command: fn(&'a str) -> Command<'a>, usage: &str| {
+            if argument.is_empty() {
+                Err(format!("usage: {usage}"))
+            } else {
+                Ok(command(argument))
+            }
+        };
+
+        match name {
+            ":help" => Ok(Command::Help),
+            ":load" => needs_argument(Command::Load, ":load <file>"),
+            ":reset" => Ok(Command::Reset),
+            ":env" => Ok(Command::Env),
+            ":ast" => needs_argument(Command::Ast, ":ast <code>"),
+            ":tokens" => needs_argument(Command::Tokens, ":tokens <code>"),
+            ":time" => needs_argument(Command::Time, ":time <code>"),
+            _ => Err(format!(
+                "unknown command '{name}', type :help for a list of commands"
+            )),
+        }
+    }
+
+    /// Runs the command against the session. `line_number` is the session
+    /// line the command was entered on and is used when reporting errors in
+    /// any code it was given. Returns the number of session lines used: a
+    /// loaded file takes up one per line of the file.
+    pub fn execute(&self, kestrl: &mut Kestrl, line_number: usize) -> usize {
+        match self {
+            Command::Help => {
+                for (usage, description) in COMMANDS {
+                    println!("  {usage:<16} {description}");
+                }
+            }
+            Command::Load(path) => match std::fs::read_to_string(path) {
+                Ok(contents) => {
+                    if let Err(error) = kestrl.load(&contents, line_number) {
+                        kestrl.report(&error, &contents, line_number);
+                    }
+                    return contents.lines().count().max(1);
+                }
+                Err(error) => println!("could not read {path}: {error}"),
+            },
+            Command::Reset => {
+                kestrl.reset();
+                println!("session reset");
+            }
+            Command::Env => {
+                for (name, value) in kestrl.globals() {
+                    println!("{name} = {}", value.repr());
+                }
+            }
+            Command::Ast(code) => match Kestrl::parse(code, line_number) {
+                Ok(statements) => println!("{}", AstPrinter.print_program(&statements)),
+                Err(error) => kestrl.report(&error, code, line_number),
+            },
+            Command::Tokens(code) => match Kestrl::scan(code, line_number) {
+                Ok(tokens) => {
+                    for token in tokens {
+                        println!("{}:{} {token}", token.span.line, token.span.column);
+                    }
+                }
+                Err(error) => kestrl.report(&error, code, line_number),
+            },
+            Command::Time(code) => {
+                let start = Instant::now();
+                if let Err(error) = kestrl.run_line(code, line_number) {
+                    kestrl.report(&error, code, line_number);
+                }
+                println!("took {:?}", start.elapsed());
+            }
+        }
+        1
+    }
+}
+
+/// Decides whether `input` is a complete unit the REPL can submit, or
+/// whether it should keep reading lines. Input is incomplete while brackets
+/// are left open, a string is unterminated, or the only problems the
+/// scanner and parser find are caused by running out of input.
+pub fn is_complete(input: &str) -> bool {
+    let mut diagnostics = Vec::new();
+    let mut reporter = |diagnostic: Diagnostic| diagnostics.push(diagnostic);
+    let tokens = Scanner::new(input).scan_tokens(&mut reporter);
+
+    let mut depth: isize = 0;
+    for token in &tokens {
+        match token.token_type {
+            TokenType::LEFT_PAREN | TokenType::LEFT_BRACE => depth += 1,
+            TokenType::RIGHT_PAREN | TokenType::RIGHT_BRACE => depth -= 1,
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return false;
+    }
+
+    if !diagnostics.is_empty() {
+        return !diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.unexpected_eof);
+    }
+
+    match Parser::new(tokens).parse() {
+        Ok(_) => true,
+        Err(errors) => !errors
+            .into_iter()
+            .map(Diagnostic::from)
+            .any(|diagnostic| diagnostic.unexpected_eof),
+    }
+}
+
+/// Words offered by tab completion: the language keywords and the session's
+/// globals.
+pub fn completion_words(kestrl: &Kestrl) -> Vec<String> {
+    KEYWORDS
+        .iter()
+        .map(|(keyword, _)| keyword.to_string())
+        .chain(kestrl.globals().into_iter().map(|(name, _)| name))
+        .collect()
+}
@@ -0,0 +1,202 @@
+use std::{
+    io::{self, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+};
+
+/// A key press, decoded from the bytes a terminal sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Escape,
+    /// A character typed with the control key held, such as `Ctrl('r')`.
+    Ctrl(char),
+}
+
+/// What the line editor needs from a terminal. The real implementation talks
+/// to the process's tty; tests drive the editor with a scripted fake.
+pub trait Terminal {
+    /// Returns the next key press, or `None` once the input is exhausted.
+    fn read_key(&mut self) -> io::Result<Option<Key>>;
+
+    fn write(&mut self, text: &str) -> io::Result<()>;
+
+    /// Called before the editor starts reading a line.
+    fn begin_line(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called once the editor has finished reading a line, so the terminal
+    /// can be handed back in a state suitable for ordinary output.
+    fn end_line(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The process's own terminal. Raw mode is switched on with `stty` only
+/// while a line is being read, so program output in between behaves as
+/// usual.
+pub struct StdTerminal {
+    saved_mode: Option<String>,
+}
+
+impl StdTerminal {
+    /// Returns `None` when stdin is not an interactive terminal or `stty` is
+    /// unavailable, in which case the caller should read lines plainly.
+    pub fn new() -> Option<Self> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        stty(&["-g"]).ok().map(|_| StdTerminal { saved_mode: None })
+    }
+}
+
+impl Terminal for StdTerminal {
+    fn read_key(&mut self) -> io::Result<Option<Key>> {
+        // Reads give up after a tenth of a second (see `begin_line`), so
+        // nothing read here means no key has been pressed yet.
+        let mut stdin = io::stdin().lock();
+        loop {
+            if let Some(key) = read_key(&mut stdin)? {
+                return Ok(Some(key));
+            }
+        }
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()
+    }
+
+    fn begin_line(&mut self) -> io::Result<()> {
+        self.saved_mode = Some(stty(&["-g"])?);
+        // `min 0 time 1` makes a read return empty after 0.1s without input,
+        // which is how a lone Escape is told apart from an escape sequence.
+        stty(&["raw", "-echo", "min", "0", "time", "1"])?;
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        match self.saved_mode.take() {
+            Some(mode) => stty(&[mode.as_str()]).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for StdTerminal {
+    fn drop(&mut self) {
+        let _ = self.end_line();
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Decodes one key press from raw terminal input, including UTF-8 characters
+/// and the ANSI escape sequences sent by arrow, home, end and delete keys.
+/// An escape byte followed by an empty read is a press of Escape itself.
+pub fn read_key(input: &mut impl Read) -> io::Result<Option<Key>> {
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        b'\t' => Key::Tab,
+        0x7f | 0x08 => Key::Backspace,
+        0x1b => read_escape(input)?,
+        0x01..=0x1a => Key::Ctrl((b'a' + byte - 1) as char),
+        _ if byte < 0x80 => Key::Char(byte as char),
+        _ => {
+            let len = match byte {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                _ => 4,
+            };
+            let mut bytes = vec![byte];
+            for _ in 1..len {
+                match read_byte(input)? {
+                    Some(next) => bytes.push(next),
+                    None => break,
+                }
+            }
+            match std::str::from_utf8(&bytes)
+                .ok()
+                .and_then(|s| s.chars().next())
+            {
+                Some(c) => Key::Char(c),
+                None => Key::Char(char::REPLACEMENT_CHARACTER),
+            }
+        }
+    };
+    Ok(Some(key))
+}
+
+fn read_escape(input: &mut impl Read) -> io::Result<Key> {
+    let key = match read_byte(input)? {
+        Some(b'[') => match read_byte(input)? {
+            Some(b'A') => Key::Up,
+            Some(b'B') => Key::Down,
+            Some(b'C') => Key::Right,
+            Some(b'D') => Key::Left,
+            Some(b'H') => Key::Home,
+            Some(b'F') => Key::End,
+            Some(digit @ b'0'..=b'9') => {
+                // `ESC [ n ~` sequences; skip anything up to the final `~`.
+                let mut last = digit;
+                while last != b'~' {
+                    match read_byte(input)? {
+                        Some(next) => last = next,
+                        None => break,
+                    }
+                }
+                match digit {
+                    b'1' | b'7' => Key::Home,
+                    b'3' => Key::Delete,
+                    b'4' | b'8' => Key::End,
+                    _ => Key::Escape,
+                }
+            }
+            _ => Key::Escape,
+        },
+        Some(b'O') => match read_byte(input)? {
+            Some(b'H') => Key::Home,
+            Some(b'F') => Key::End,
+            _ => Key::Escape,
+        },
+        _ => Key::Escape,
+    };
+    Ok(key)
+}
+
+fn read_byte(input: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0u8];
+    loop {
+        match input.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
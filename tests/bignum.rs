@@ -0,0 +1,190 @@
+//! Big integers and decimals: literals, constructors, arithmetic and the
+//! conversions between them and the other number types.
+
+use kestrl::{
+    interpreter::{bigint::BigInt, convert::FromValue, decimal::Decimal, interpreter::Value},
+    kestrl::Kestrl,
+};
+
+fn repr(source: &str) -> String {
+    Kestrl::new(source).run().unwrap().repr()
+}
+
+fn error(source: &str) -> String {
+    Kestrl::new(source).run().unwrap_err().diagnostics()[0]
+        .message
+        .clone()
+}
+
+#[test]
+fn big_integer_literals_and_arithmetic() {
+    assert_eq!(repr("2n;"), "2n");
+    assert_eq!(repr("9223372036854775808n;"), "9223372036854775808n");
+    assert_eq!(
+        repr("0xFFFF_FFFF_FFFF_FFFF_FFFFn;"),
+        "1208925819614629174706175n"
+    );
+    assert_eq!(repr("0b1n;"), "1n");
+    assert_eq!(
+        repr("9223372036854775807n * 9223372036854775807n;"),
+        "85070591730234615847396907784232501249n"
+    );
+    assert_eq!(
+        repr("1n - 100000000000000000000n;"),
+        "-99999999999999999999n"
+    );
+    assert_eq!(repr("-(3n);"), "-3n");
+    assert_eq!(repr("-7n ~/ 2n;"), "-4n");
+    assert_eq!(repr("-7n % 2n;"), "1n");
+    assert_eq!(
+        repr("100000000000000000000n ~/ 3000000000000n;"),
+        "33333333n"
+    );
+}
+
+#[test]
+fn integers_widen_to_big_integers_instead_of_overflowing() {
+    assert_eq!(repr("9223372036854775807 + 1n;"), "9223372036854775808n");
+    assert_eq!(repr("2n * 3;"), "6n");
+    assert_eq!(repr("3n == 3;"), "true");
+    assert_eq!(repr("3n < 4;"), "true");
+}
+
+#[test]
+fn decimal_literals_and_arithmetic_are_exact() {
+    assert_eq!(repr("0.1d + 0.2d;"), "0.3d");
+    assert_eq!(repr("0.1d + 0.2d == 0.3d;"), "true");
+    assert_eq!(repr("19.99d * 3;"), "59.97d");
+    assert_eq!(repr("1.50d + 1;"), "2.50d");
+    assert_eq!(repr("1.5d == 1.50d;"), "true");
+    assert_eq!(repr("5d;"), "5d");
+    assert_eq!(repr("-(1.25d);"), "-1.25d");
+    assert_eq!(repr("0.05d - 0.1d;"), "-0.05d");
+    assert_eq!(repr("1.5d < 2;"), "true");
+    assert_eq!(repr("-7.5d ~/ 2;"), "-4d");
+    assert_eq!(repr("-7.5d % 2;"), "0.5d");
+}
+
+#[test]
+fn division_keeps_exact_types_exact() {
+    assert_eq!(repr("10d / 3;"), "3.33333333333333333333d");
+    assert_eq!(repr("20d / 3;"), "6.66666666666666666667d");
+    assert_eq!(repr("1d / 8;"), "0.125d");
+    assert_eq!(repr("2.50d / 2;"), "1.25d");
+    assert_eq!(repr("7n / 2;"), "3.5d");
+    assert_eq!(repr("100.00d / 4;"), "25.00d");
+}
+
+#[test]
+fn mixing_with_a_float_gives_a_float() {
+    assert_eq!(repr("2n + 1.5;"), "3.5");
+    assert_eq!(repr("1.5d + 0.5;"), "2.0");
+    assert_eq!(repr("3n > 2.5;"), "true");
+}
+
+#[test]
+fn constructors_convert_other_values() {
+    assert_eq!(
+        repr("bigint(\"123456789012345678901234567890\") + 1;"),
+        "123456789012345678901234567891n"
+    );
+    assert_eq!(repr("bigint(42);"), "42n");
+    assert_eq!(repr("bigint(1e20);"), "100000000000000000000n");
+    assert_eq!(repr("decimal(\"-12.50\");"), "-12.50d");
+    assert_eq!(repr("decimal(0.1) + decimal(0.2);"), "0.3d");
+    assert_eq!(repr("decimal(3n);"), "3d");
+    assert_eq!(repr("\"${decimal(\"1.10\")}\";"), "\"1.10\"");
+
+    assert_eq!(
+        error("bigint(\"abc\");"),
+        "Cannot convert \"abc\" to a big integer."
+    );
+    assert_eq!(
+        error("bigint(1.5);"),
+        "Cannot convert 1.5 to a big integer."
+    );
+    assert_eq!(error("decimal(nil);"), "Cannot convert nil to a decimal.");
+    assert_eq!(
+        error("decimal(\"_\");"),
+        "Cannot convert \"_\" to a decimal."
+    );
+}
+
+#[test]
+fn round_brings_decimals_back_to_a_number_of_places() {
+    assert_eq!(
+        repr("var x = 100.00d; for (var i = 0; i < 12; i = i + 1) x = x * 1.05d; x;"),
+        "179.58563260221291503906250000d"
+    );
+    assert_eq!(
+        repr("var x = 100.00d; for (var i = 0; i < 12; i = i + 1) x = round(x * 1.05d, 2); x;"),
+        "179.59d"
+    );
+    // Ties go to the even neighbour, on either side of zero.
+    assert_eq!(repr("round(2.5d, 0);"), "2d");
+    assert_eq!(repr("round(3.5d, 0);"), "4d");
+    assert_eq!(repr("round(-2.5d, 0);"), "-2d");
+    assert_eq!(repr("round(2.675d, 2);"), "2.68d");
+    assert_eq!(repr("round(-1.006d, 2);"), "-1.01d");
+    // Digits are never added.
+    assert_eq!(repr("round(1.5d, 3);"), "1.5d");
+    assert_eq!(repr("round(7, 2);"), "7d");
+    assert_eq!(repr("round(12n, 0);"), "12d");
+
+    assert_eq!(
+        error("round(1.5d, -1);"),
+        "Cannot round to a negative number of places."
+    );
+    assert_eq!(
+        error("round(1.5, 0);"),
+        "Expected a decimal but got a number."
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(error("1n / 0;"), "Division by zero.");
+    assert_eq!(error("1d % 0n;"), "Division by zero.");
+    assert_eq!(
+        error("1n + \"a\";"),
+        "Operands must be two numbers or two strings."
+    );
+    assert_eq!(
+        error("print 2.5n;"),
+        "Big integer literals cannot have a fraction or exponent."
+    );
+    assert_eq!(
+        error("print 1e5d;"),
+        "Decimal literals cannot have an exponent."
+    );
+    assert_eq!(error("print 5x;"), "Invalid suffix 'x' on number literal.");
+}
+
+#[test]
+fn rust_conversions() {
+    let big = BigInt::parse("-123_456_789_012_345_678_901", 10).unwrap();
+    assert_eq!(big.to_string(), "-123456789012345678901");
+    assert_eq!(format!("{:>8}", BigInt::from(-42)), "     -42");
+    assert_eq!(BigInt::parse("ff", 16), Some(BigInt::from(255)));
+    assert_eq!(BigInt::parse("12a", 10), None);
+    assert_eq!(BigInt::parse("-", 10), None);
+    for text in ["", "_", "-_", ".", "_._"] {
+        assert!(Decimal::parse(text).is_none(), "{text:?}");
+    }
+    assert_eq!(Decimal::parse(".5").unwrap().to_string(), "0.5");
+
+    let mut kestrl = Kestrl::new("");
+    kestrl.set_global("price", Decimal::parse("19.99").unwrap());
+    kestrl.set_global("count", BigInt::from(3));
+    let total = kestrl.eval("price * count;").unwrap();
+    assert_eq!(total.to_string(), "59.97");
+    assert_eq!(
+        Decimal::from_value(&total).unwrap(),
+        Decimal::parse("59.970").unwrap()
+    );
+    assert_eq!(BigInt::from_value(&Value::Int(7)).unwrap(), BigInt::from(7));
+    assert_eq!(i64::from_value(&Value::BigInt(BigInt::from(7))).unwrap(), 7);
+    assert!(i64::from_value(&kestrl.eval("2n * 9223372036854775807;").unwrap()).is_err());
+    assert_eq!(f64::from_value(&total).unwrap(), 59.97);
+    assert!(Decimal::from_value(&Value::Number(1.5)).is_err());
+}
@@ -0,0 +1,104 @@
+//! Classes, instances, initializers and inheritance.
+
+use kestrl::{
+    interpreter::output::Buffer,
+    kestrl::{Kestrl, KestrlError},
+};
+
+/// Everything the script prints.
+fn output(source: &str) -> String {
+    let output = Buffer::new();
+    let mut kestrl = Kestrl::new(source);
+    kestrl.set_output(output.clone());
+    kestrl.run().unwrap();
+    output.contents()
+}
+
+fn runtime_error(source: &str) -> String {
+    match Kestrl::new(source).run() {
+        Err(KestrlError::Runtime(diagnostic)) => diagnostic.message,
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+}
+
+#[test]
+fn init_returns_the_instance() {
+    let source = "class Point {
+                      init(x) {
+                          this.x = x;
+                          if (x < 0) return;
+                          this.positive = true;
+                      }
+                  }
+                  var p = Point(1);
+                  print p;
+                  print p.init(2) == p;
+                  print p.x;
+                  var n = Point(-1);
+                  print n.init(-2);
+                  print n.x;";
+    assert_eq!(
+        output(source),
+        "Point instance\ntrue\n2\nPoint instance\n-2\n"
+    );
+}
+
+#[test]
+fn subclasses_inherit_and_call_super() {
+    let source = "class A {
+                      greet() { return \"A\"; }
+                      name() { return \"a\"; }
+                  }
+                  class B < A {
+                      greet() { return \"B then \" + super.greet(); }
+                  }
+                  var b = B();
+                  print b.greet();
+                  print b.name();";
+    assert_eq!(output(source), "B then A\na\n");
+}
+
+#[test]
+fn super_is_bound_to_the_defining_class() {
+    let source = "class A { method() { print \"A\"; } }
+                  class B < A { test() { super.method(); } }
+                  class C < B { method() { print \"C\"; } }
+                  C().test();";
+    assert_eq!(output(source), "A\n");
+}
+
+#[test]
+fn fields_shadow_methods() {
+    let source = "class A { f() { return \"method\"; } }
+                  var a = A();
+                  print a.f();
+                  fun field() { return \"field\"; }
+                  a.f = field;
+                  print a.f();
+                  print A().f();";
+    assert_eq!(output(source), "method\nfield\nmethod\n");
+}
+
+#[test]
+fn missing_properties_are_runtime_errors() {
+    assert_eq!(
+        runtime_error("class A {} print A().missing;"),
+        "Undefined property 'missing'."
+    );
+    assert_eq!(
+        runtime_error("class A {} class B < A { f() { super.missing(); } } B().f();"),
+        "Undefined property 'missing'."
+    );
+}
+
+#[test]
+fn superclasses_must_be_classes() {
+    assert_eq!(
+        runtime_error("var NotAClass = \"x\"; class A < NotAClass {}"),
+        "Superclass must be a class."
+    );
+    assert_eq!(
+        runtime_error("fun f() {} class A < f {}"),
+        "Superclass must be a class."
+    );
+}
@@ -0,0 +1,131 @@
+//! Runs the `kestrl` binary and checks its output and exit codes.
+
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn kestrl(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_kestrl"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The process may exit without reading its input, so a broken pipe here
+    // is expected.
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn inline_code_runs_and_receives_arguments() {
+    let output = kestrl(&["-e", "print argc(); print argv(1);", "first"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "2\nfirst\n");
+}
+
+#[test]
+fn scripts_can_be_read_from_stdin() {
+    let output = kestrl(&["run", "-", "a", "b"], "print argv(0) + argv(2);");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "-b\n");
+}
+
+#[test]
+fn compile_errors_exit_with_65() {
+    for args in [
+        &["-e", "print 1 +;"][..],
+        &["-e", "return 1;"],
+        &["check", "-"],
+    ] {
+        let output = kestrl(args, "var a = ;");
+        assert_eq!(output.status.code(), Some(65), "{args:?}");
+        assert!(stderr(&output).contains("error[E000"), "{args:?}");
+    }
+}
+
+#[test]
+fn runtime_errors_exit_with_70() {
+    let output = kestrl(&["-e", "print 1; print nope;"], "");
+    assert_eq!(output.status.code(), Some(70));
+    assert_eq!(stdout(&output), "1\n");
+    assert!(stderr(&output).contains("Undefined variable 'nope'."));
+}
+
+#[test]
+fn check_does_not_run_the_script() {
+    let output = kestrl(&["check", "-"], "print \"ran\";");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn tokens_and_ast_dump_the_front_end_output() {
+    let tokens = kestrl(&["tokens", "-"], "print 1;");
+    assert_eq!(
+        stdout(&tokens),
+        "1:1 PRINT Str(\"print\")\n1:7 NUMBER Int(1)\n1:8 SEMICOLON Str(\";\")\n1:9 EOF Nil\n"
+    );
+
+    let ast = kestrl(&["ast", "-"], "print 1 + 2 * 3;");
+    assert_eq!(stdout(&ast), "(print (+ 1 (* 2 3)))\n");
+}
+
+#[test]
+fn bad_usage_exits_with_64() {
+    for args in [&["--bogus"][..], &["check"], &["ast", "a", "b"], &["-e"]] {
+        let output = kestrl(args, "");
+        assert_eq!(output.status.code(), Some(64), "{args:?}");
+        assert!(stderr(&output).contains("Usage: kestrl"), "{args:?}");
+    }
+}
+
+#[test]
+fn missing_files_exit_with_66() {
+    let output = kestrl(&["run", "does-not-exist.kst"], "");
+    assert_eq!(output.status.code(), Some(66));
+}
+
+#[test]
+fn repl_errors_are_shown_against_the_input_they_come_from() {
+    let library = std::env::temp_dir().join(format!("kestrl-lib-{}.kst", std::process::id()));
+    std::fs::write(&library, "fun g() { return 2 * missing; }\n").unwrap();
+    let session = format!(
+        "fun f() {{\n  return 1 + nope;\n}}\nvar a = 1;\n:load {}\nprint a; f();\ng();\n",
+        library.display()
+    );
+
+    let output = kestrl(&["repl"], &session);
+    std::fs::remove_file(&library).unwrap();
+    let errors = stderr(&output);
+    assert!(
+        errors.contains(" --> 2:14\n  |\n2 |   return 1 + nope;\n  |              ^^^^\n"),
+        "{errors}"
+    );
+    assert!(
+        errors.contains(" --> 5:22\n  |\n5 | fun g() { return 2 * missing; }\n"),
+        "{errors}"
+    );
+}
+
+#[test]
+fn repl_reports_unfinished_input_on_its_last_line() {
+    let output = kestrl(&["repl"], "var a = 1;\nprint (1 +\n\nprint b;\n");
+    let errors = stderr(&output);
+    assert!(
+        errors.contains(" --> 2:11\n  |\n2 | print (1 +\n  |           ^\n"),
+        "{errors}"
+    );
+    assert!(errors.contains(" --> 4:7\n"), "{errors}");
+}
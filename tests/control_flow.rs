@@ -0,0 +1,78 @@
+//! Conditionals, loops and the short-circuiting logical operators.
+
+use kestrl::{
+    interpreter::output::Buffer,
+    kestrl::{Kestrl, KestrlError},
+};
+
+/// Everything the script prints.
+fn output(source: &str) -> String {
+    let output = Buffer::new();
+    let mut kestrl = Kestrl::new(source);
+    kestrl.set_output(output.clone());
+    kestrl.run().unwrap();
+    output.contents()
+}
+
+#[test]
+fn if_picks_a_branch_by_truthiness() {
+    let source = "if (nil) print 1; else print 2;
+                  if (0) print 3; else print 4;
+                  if (\"\") print 5;
+                  if (false) print 6; else if (true) print 7; else print 8;";
+    assert_eq!(output(source), "2\n3\n5\n7\n");
+}
+
+#[test]
+fn while_runs_until_its_condition_is_false() {
+    let source = "var i = 0;
+                  while (i < 3) { print i; i = i + 1; }
+                  while (false) print \"never\";
+                  print i;";
+    assert_eq!(output(source), "0\n1\n2\n3\n");
+}
+
+#[test]
+fn for_loops_run_each_clause() {
+    let source = "for (var i = 0; i < 3; i = i + 1) print i;
+                  var j = 10;
+                  for (; j < 12;) { print j; j = j + 1; }
+                  for (j = 0; j < 1; j = j + 1) print \"once\";";
+    assert_eq!(output(source), "0\n1\n2\n10\n11\nonce\n");
+}
+
+#[test]
+fn for_loop_variables_are_scoped_to_the_loop() {
+    assert_eq!(
+        output("var i = \"outer\"; for (var i = 0; i < 2; i = i + 1) {} print i;"),
+        "outer\n"
+    );
+    match Kestrl::new("for (var k = 0; k < 1; k = k + 1) {} print k;").run() {
+        Err(KestrlError::Runtime(diagnostic)) => {
+            assert_eq!(diagnostic.message, "Undefined variable 'k'.");
+        }
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+}
+
+#[test]
+fn and_or_short_circuit() {
+    let source = "var calls = 0;
+                  fun f() { calls = calls + 1; return true; }
+                  print false and f();
+                  print true or f();
+                  print calls;
+                  print true and f();
+                  print false or f();
+                  print calls;";
+    assert_eq!(output(source), "false\ntrue\n0\ntrue\ntrue\n2\n");
+}
+
+#[test]
+fn and_or_return_an_operand() {
+    let source = "print nil or \"default\";
+                  print 1 and 2;
+                  print nil and 2;
+                  print 0 or 3;";
+    assert_eq!(output(source), "default\n2\nnil\n0\n");
+}
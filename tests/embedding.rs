@@ -0,0 +1,329 @@
+//! Uses `kestrl` as a library, the way a host application would.
+
+use kestrl::{
+    interpreter::{diagnostics::RUNTIME_ERROR, interpreter::Value, output::Buffer},
+    kestrl::{Kestrl, KestrlError, with_stack},
+};
+use std::io::{self, Write};
+
+#[test]
+fn eval_returns_the_value_of_the_final_expression() {
+    let mut kestrl = Kestrl::new("");
+    assert_eq!(kestrl.eval("1 + 2;"), Ok(Value::Int(3)));
+    assert_eq!(kestrl.eval("var a = 1;"), Ok(Value::Nil));
+    assert_eq!(
+        kestrl.eval("a = a + 1; \"kest\" + \"rl\";"),
+        Ok(Value::String("kestrl".to_string()))
+    );
+    assert_eq!(kestrl.eval("a;"), Ok(Value::Int(2)));
+}
+
+#[test]
+fn run_uses_the_session_source() {
+    let mut kestrl = Kestrl::new("fun twice(n) { return n * 2; } twice(21);");
+    assert_eq!(kestrl.run(), Ok(Value::Int(42)));
+}
+
+#[test]
+fn errors_identify_their_phase_and_position() {
+    let mut kestrl = Kestrl::new("");
+
+    let Err(KestrlError::Scan(errors)) = kestrl.eval("var a = @;") else {
+        panic!("expected a scan error");
+    };
+    assert_eq!(errors[0].span.unwrap().column, 9);
+
+    let Err(KestrlError::Parse(errors)) = kestrl.eval("print (1;\nprint ;") else {
+        panic!("expected parse errors");
+    };
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[1].span.unwrap().line, 2);
+
+    let Err(KestrlError::Resolve(errors)) = kestrl.eval("return 1;") else {
+        panic!("expected a resolve error");
+    };
+    assert_eq!(errors[0].message, "Can't return from top-level code.");
+
+    let error = kestrl.eval("\n  nope;").unwrap_err();
+    let KestrlError::Runtime(diagnostic) = &error else {
+        panic!("expected a runtime error");
+    };
+    assert_eq!(diagnostic.code, RUNTIME_ERROR);
+    let span = diagnostic.span.unwrap();
+    assert_eq!((span.line, span.column), (2, 3));
+    assert_eq!(
+        error.to_string(),
+        "runtime error at 2:3: Undefined variable 'nope'."
+    );
+}
+
+#[test]
+fn errors_work_as_std_errors() {
+    fn run(source: &str) -> Result<Value, Box<dyn std::error::Error>> {
+        Ok(Kestrl::new(source).run()?)
+    }
+
+    let error = run("print 1 +;\nprint -;").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "parse error at 1:10: Expect expression. (and 1 more)"
+    );
+}
+
+#[test]
+fn errors_render_against_their_source() {
+    let source = "print 1 +;";
+    let error = Kestrl::new(source).run().unwrap_err();
+    assert!(
+        error
+            .render(source, 1, false)
+            .starts_with("error[E0002]: Expect expression.\n --> 1:10\n")
+    );
+}
+
+#[test]
+fn carets_line_up_under_tab_indented_code() {
+    let source = "{\n\t\tprint 1 +;\n}";
+    let error = Kestrl::new(source).run().unwrap_err();
+    assert!(
+        error
+            .render(source, 1, false)
+            .contains("2 | \t\tprint 1 +;\n  | \t\t         ^\n")
+    );
+}
+
+#[test]
+fn print_writes_to_the_output_sink() {
+    let output = Buffer::new();
+    let mut kestrl = Kestrl::new("print 1; print \"two\"; print nil;");
+    kestrl.set_output(output.clone());
+
+    kestrl.run().unwrap();
+    assert_eq!(output.take(), "1\ntwo\nnil\n");
+
+    kestrl.eval("print 3 * 4;").unwrap();
+    assert_eq!(output.contents(), "12\n");
+}
+
+#[test]
+fn repl_echo_writes_to_the_output_sink() {
+    let output = Buffer::new();
+    let mut kestrl = Kestrl::new("");
+    kestrl.set_output(output.clone());
+
+    kestrl
+        .run_line("var a = \"hi\"; a; nil; 1 + 1;", 1)
+        .unwrap();
+    assert_eq!(output.contents(), "\"hi\"\n2\n");
+}
+
+#[test]
+fn reported_errors_go_to_the_error_sink_without_colour() {
+    let output = Buffer::new();
+    let errors = Buffer::new();
+    let mut kestrl = Kestrl::new("");
+    kestrl.set_output(output.clone());
+    kestrl.set_error_output(errors.clone());
+
+    let source = "print 1;\nprint nope;";
+    let error = kestrl.eval(source).unwrap_err();
+    assert_eq!(
+        errors.contents(),
+        "",
+        "errors are only written when reported"
+    );
+
+    kestrl.report(&error, source, 1);
+    assert_eq!(output.contents(), "1\n");
+    assert_eq!(
+        errors.contents(),
+        "error[E0004]: Undefined variable 'nope'.\n --> 2:7\n  |\n2 | print nope;\n  |       ^^^^\n"
+    );
+}
+
+#[test]
+fn errors_outside_the_reported_source_have_no_snippet() {
+    let errors = Buffer::new();
+    let mut kestrl = Kestrl::new("");
+    kestrl.set_error_output(errors.clone());
+
+    kestrl.eval("fun f() {\n  return nope;\n}").unwrap();
+    let source = "f();";
+    let error = kestrl.eval(source).unwrap_err();
+    kestrl.report(&error, source, 1);
+    assert_eq!(
+        errors.contents(),
+        "error[E0004]: Undefined variable 'nope'.\n --> 2:10\n"
+    );
+}
+
+#[test]
+fn output_sinks_survive_a_reset() {
+    let output = Buffer::new();
+    let mut kestrl = Kestrl::new("");
+    kestrl.set_output(output.clone());
+
+    kestrl.eval("var a = 1;").unwrap();
+    kestrl.reset();
+    assert!(kestrl.eval("print a;").is_err());
+    kestrl.eval("print 2;").unwrap();
+    assert_eq!(output.contents(), "2\n");
+}
+
+#[test]
+fn failing_output_is_a_runtime_error() {
+    struct Closed;
+
+    impl Write for Closed {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut kestrl = Kestrl::new("print 1;");
+    kestrl.set_output(Closed);
+    let Err(KestrlError::Runtime(diagnostic)) = kestrl.run() else {
+        panic!("expected a runtime error");
+    };
+    assert!(diagnostic.message.starts_with("Could not write output"));
+}
+
+#[test]
+fn globals_can_be_read_and_set_from_rust() {
+    let mut kestrl = Kestrl::new("");
+    kestrl.set_global("limit", 10.0);
+    kestrl.set_global("name", "kestrl");
+    kestrl.set_global("missing", None::<f64>);
+
+    kestrl
+        .eval("var doubled = limit * 2; var greeting = name + \"!\";")
+        .unwrap();
+    assert_eq!(kestrl.get_global("doubled"), Some(Value::Number(20.0)));
+    assert_eq!(
+        kestrl.get_global("greeting"),
+        Some(Value::String("kestrl!".to_string()))
+    );
+    assert_eq!(kestrl.get_global("missing"), Some(Value::Nil));
+    assert_eq!(kestrl.get_global("undefined"), None);
+}
+
+#[test]
+fn script_functions_can_be_called_from_rust() {
+    let mut kestrl = Kestrl::new(
+        "var calls = 0;
+         fun on_request(path, size) {
+             calls = calls + 1;
+             if (size > 100) return \"reject:\" + path;
+             return nil;
+         }",
+    );
+    kestrl.run().unwrap();
+
+    let hook = kestrl.get_global("on_request").unwrap();
+    assert_eq!(
+        kestrl.call(&hook, vec![Value::from("/a"), Value::from(10.0)]),
+        Ok(Value::Nil)
+    );
+    assert_eq!(
+        kestrl.call(&hook, vec![Value::from("/b"), Value::from(500.0)]),
+        Ok(Value::String("reject:/b".to_string()))
+    );
+    assert_eq!(kestrl.get_global("calls"), Some(Value::Int(2)));
+}
+
+#[test]
+fn classes_and_closures_can_be_called_from_rust() {
+    let mut kestrl = Kestrl::new("");
+    let counter = kestrl
+        .eval(
+            "fun make_counter() {
+                 var n = 0;
+                 fun next() { n = n + 1; return n; }
+                 return next;
+             }
+             class Point { init(x, y) { this.x = x; this.y = y; } }
+             make_counter();",
+        )
+        .unwrap();
+
+    kestrl.call(&counter, vec![]).unwrap();
+    assert_eq!(kestrl.call(&counter, vec![]), Ok(Value::Int(2)));
+
+    let point_class = kestrl.get_global("Point").unwrap();
+    let point = kestrl
+        .call(&point_class, vec![Value::from(1), Value::from(2)])
+        .unwrap();
+    kestrl.set_global("p", point);
+    assert_eq!(kestrl.eval("p.x + p.y;"), Ok(Value::Int(3)));
+}
+
+#[test]
+fn bad_calls_from_rust_are_runtime_errors() {
+    let mut kestrl = Kestrl::new("fun f(a) { return a.field; }");
+    kestrl.run().unwrap();
+    let f = kestrl.get_global("f").unwrap();
+
+    // There is no call expression to point at, so no position either.
+    let error = kestrl.call(&f, vec![]).unwrap_err();
+    assert_eq!(error.diagnostics()[0].span, None);
+    assert_eq!(
+        error.to_string(),
+        "runtime error: Expected 1 arguments but got 0."
+    );
+    assert_eq!(
+        error.render("", 1, false),
+        "error[E0004]: Expected 1 arguments but got 0.\n"
+    );
+
+    let error = kestrl.call(&f, vec![Value::from(1.0)]).unwrap_err();
+    let KestrlError::Runtime(diagnostic) = error else {
+        panic!("expected a runtime error");
+    };
+    assert_eq!(diagnostic.message, "Only instances have properties.");
+    assert_eq!(diagnostic.span.unwrap().column, 21);
+
+    let error = kestrl.call(&Value::from(1.0), vec![]).unwrap_err();
+    assert_eq!(
+        error.diagnostics()[0].message,
+        "Can only call functions and classes."
+    );
+}
+
+#[test]
+fn runaway_recursion_is_a_runtime_error() {
+    with_stack(|| {
+        let mut kestrl = Kestrl::new("");
+        for source in [
+            "fun f(n) { return f(n + 1); } f(0);",
+            "fun g() { g(); } g();",
+        ] {
+            let Err(KestrlError::Runtime(diagnostic)) = kestrl.eval(source) else {
+                panic!("expected a runtime error");
+            };
+            assert_eq!(diagnostic.message, "Stack overflow.");
+        }
+
+        // The depth is unwound with the error, so the session stays usable.
+        assert_eq!(
+            kestrl.eval("fun h(n) { if (n > 0) return h(n - 1); return \"done\"; } h(100);"),
+            Ok(Value::String("done".to_string()))
+        );
+    });
+}
+
+#[test]
+fn ordinary_deep_recursion_succeeds() {
+    with_stack(|| {
+        let mut kestrl = Kestrl::new(
+            "fun count(n) { if (n > 0) return 1 + count(n - 1); return 0; }
+             class List { init(n) { if (n > 0) this.next = List(n - 1); else this.next = nil; } }",
+        );
+        kestrl.run().unwrap();
+        assert_eq!(kestrl.eval("count(1000);"), Ok(Value::Int(1000)));
+        assert!(kestrl.eval("List(1000);").is_ok());
+    });
+}
@@ -0,0 +1,288 @@
+//! Drives the REPL line editor with a scripted terminal instead of a tty.
+
+use kestrl::repl::{
+    editor::{LineEditor, ReadLine},
+    history::{History, MAX_ENTRIES},
+    terminal::{Key, Terminal, read_key},
+};
+use std::{collections::VecDeque, io};
+
+/// A terminal that replays a fixed list of key presses and records
+/// everything written to it.
+struct ScriptedTerminal {
+    keys: VecDeque<Key>,
+    output: String,
+}
+
+impl ScriptedTerminal {
+    fn new(keys: Vec<Key>) -> Self {
+        ScriptedTerminal {
+            keys: keys.into(),
+            output: String::new(),
+        }
+    }
+}
+
+impl Terminal for ScriptedTerminal {
+    fn read_key(&mut self) -> io::Result<Option<Key>> {
+        Ok(self.keys.pop_front())
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.output.push_str(text);
+        Ok(())
+    }
+}
+
+fn typed(text: &str) -> Vec<Key> {
+    text.chars().map(Key::Char).collect()
+}
+
+fn editor(keys: Vec<Key>, history: &[&str]) -> LineEditor<ScriptedTerminal> {
+    let mut past = History::new();
+    for line in history {
+        past.add(line).unwrap();
+    }
+    LineEditor::new(ScriptedTerminal::new(keys), past)
+}
+
+fn read(editor: &mut LineEditor<ScriptedTerminal>) -> ReadLine {
+    editor.read_line(">>>").unwrap()
+}
+
+fn line(text: &str) -> ReadLine {
+    ReadLine::Line(text.to_string())
+}
+
+#[test]
+fn typing_and_enter_submits_the_line() {
+    let mut keys = typed("print 1;");
+    keys.push(Key::Enter);
+    let mut editor = editor(keys, &[]);
+
+    assert_eq!(read(&mut editor), line("print 1;"));
+    assert_eq!(editor.history().entries(), ["print 1;"]);
+    assert!(editor.terminal().output.starts_with("\r>>>"));
+}
+
+#[test]
+fn cursor_movement_edits_in_the_middle_of_the_line() {
+    let mut keys = typed("pint");
+    keys.extend([Key::Home, Key::Right, Key::Char('r'), Key::End]);
+    keys.extend(typed(" 1"));
+    keys.extend([Key::Left, Key::Backspace, Key::Delete, Key::Enter]);
+    let mut editor = editor(keys, &[]);
+
+    assert_eq!(read(&mut editor), line("print"));
+}
+
+#[test]
+fn kill_commands_remove_text_around_the_cursor() {
+    let mut keys = typed("var x = 1;");
+    keys.extend([Key::Left, Key::Left, Key::Ctrl('k')]);
+    keys.extend(typed("2;"));
+    keys.extend([Key::Ctrl('a'), Key::Right, Key::Right, Key::Right]);
+    keys.extend([Key::Ctrl('u'), Key::Enter]);
+    let mut editor = editor(keys, &[]);
+
+    assert_eq!(read(&mut editor), line(" x = 2;"));
+}
+
+#[test]
+fn arrow_keys_browse_history_and_restore_the_draft() {
+    let mut keys = typed("dra");
+    keys.extend([Key::Up, Key::Up, Key::Up, Key::Down, Key::Enter]);
+    keys.extend(typed("ft"));
+    keys.extend([Key::Up, Key::Down, Key::Enter]);
+    let mut editor = editor(keys, &["first", "second"]);
+
+    assert_eq!(read(&mut editor), line("second"));
+    assert_eq!(read(&mut editor), line("ft"));
+}
+
+#[test]
+fn reverse_search_finds_older_matches() {
+    let mut keys = vec![Key::Ctrl('r')];
+    keys.extend(typed("print"));
+    keys.extend([Key::Ctrl('r'), Key::Enter]);
+    let mut editor = editor(keys, &["print 1;", "var a = 2;", "print a;"]);
+
+    assert_eq!(read(&mut editor), line("print 1;"));
+    assert!(
+        editor
+            .terminal()
+            .output
+            .contains("(reverse-i-search)`print': print a;")
+    );
+}
+
+#[test]
+fn reverse_search_can_be_accepted_for_editing_or_cancelled() {
+    let mut keys = vec![Key::Ctrl('r'), Key::Char('v'), Key::End];
+    keys.extend(typed(" // edited"));
+    keys.push(Key::Enter);
+    keys.extend([Key::Ctrl('r'), Key::Char('z'), Key::Ctrl('g'), Key::Enter]);
+    let mut editor = editor(keys, &["var a = 2;"]);
+
+    assert_eq!(read(&mut editor), line("var a = 2; // edited"));
+    assert_eq!(read(&mut editor), line(""));
+    assert!(
+        editor
+            .terminal()
+            .output
+            .contains("(failed reverse-i-search)`z'")
+    );
+}
+
+#[test]
+fn tab_completes_a_unique_match() {
+    let mut keys = typed("pr");
+    keys.push(Key::Tab);
+    keys.extend(typed(" (cou);"));
+    keys.extend([Key::Left, Key::Left, Key::Tab, Key::End, Key::Enter]);
+    let mut editor = editor(keys, &[]);
+    editor.set_completions(vec!["print".to_string(), "counter".to_string()]);
+
+    assert_eq!(read(&mut editor), line("print (counter);"));
+}
+
+#[test]
+fn tab_extends_to_the_common_prefix_then_lists_matches() {
+    let mut keys = typed("co");
+    keys.extend([Key::Tab, Key::Tab, Key::Enter]);
+    let mut editor = editor(keys, &[]);
+    editor.set_completions(vec!["counter".to_string(), "count".to_string()]);
+
+    assert_eq!(read(&mut editor), line("count"));
+    assert!(editor.terminal().output.contains("\r\ncount  counter\r\n"));
+}
+
+#[test]
+fn ctrl_c_interrupts_and_ctrl_d_ends_input() {
+    let mut keys = typed("oops");
+    keys.extend([Key::Ctrl('c'), Key::Ctrl('d')]);
+    let mut editor = editor(keys, &[]);
+
+    assert_eq!(read(&mut editor), ReadLine::Interrupted);
+    assert_eq!(read(&mut editor), ReadLine::Eof);
+    assert_eq!(read(&mut editor), ReadLine::Eof);
+    assert!(editor.history().is_empty());
+}
+
+#[test]
+fn history_is_persisted_to_its_file() {
+    let path = std::env::temp_dir().join(format!("kestrl-history-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut history = History::with_file(path.clone()).unwrap();
+    history.add("print 1;").unwrap();
+    history.add("print 1;").unwrap();
+    history.add("   ").unwrap();
+    history.add("var a;").unwrap();
+
+    let reloaded = History::with_file(path.clone()).unwrap();
+    assert_eq!(reloaded.entries(), ["print 1;", "var a;"]);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn history_file_is_kept_to_the_entry_limit() {
+    let path = std::env::temp_dir().join(format!("kestrl-capped-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut history = History::with_file(path.clone()).unwrap();
+    for i in 0..MAX_ENTRIES + 5 {
+        history.add(&format!("print {i};")).unwrap();
+    }
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().count(), MAX_ENTRIES);
+    assert_eq!(contents.lines().next(), Some("print 5;"));
+    assert_eq!(
+        History::with_file(path.clone()).unwrap().entries(),
+        history.entries()
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn unsaveable_history_warns_once_and_keeps_going() {
+    let path = std::env::temp_dir()
+        .join(format!("kestrl-missing-{}", std::process::id()))
+        .join("history");
+    let mut keys = typed("1;");
+    keys.push(Key::Enter);
+    keys.extend(typed("2;"));
+    keys.push(Key::Enter);
+    let history = History::with_file(path).unwrap();
+    let mut editor = LineEditor::new(ScriptedTerminal::new(keys), history);
+
+    assert_eq!(read(&mut editor), line("1;"));
+    assert_eq!(read(&mut editor), line("2;"));
+    assert_eq!(editor.history().entries(), ["1;", "2;"]);
+    let output = &editor.terminal().output;
+    assert_eq!(
+        output.matches("warning: history will not be saved").count(),
+        1
+    );
+}
+
+#[test]
+fn raw_bytes_decode_into_keys() {
+    let mut input: &[u8] = b"a\x1b[A\x1b[D\x1b[3~\x1bOH\x12\x7f\r\tx\xc3\xa9";
+    let mut keys = Vec::new();
+    while let Some(key) = read_key(&mut input).unwrap() {
+        keys.push(key);
+    }
+
+    assert_eq!(
+        keys,
+        [
+            Key::Char('a'),
+            Key::Up,
+            Key::Left,
+            Key::Delete,
+            Key::Home,
+            Key::Ctrl('r'),
+            Key::Backspace,
+            Key::Enter,
+            Key::Tab,
+            Key::Char('x'),
+            Key::Char('é'),
+        ]
+    );
+}
+
+/// Raw input that arrives in bursts, with an empty read standing for a
+/// terminal read that timed out between them.
+struct Bursts(VecDeque<&'static [u8]>);
+
+impl io::Read for Bursts {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(burst) = self.0.pop_front() else {
+            return Ok(0);
+        };
+        let len = burst.len().min(buf.len());
+        buf[..len].copy_from_slice(&burst[..len]);
+        if len < burst.len() {
+            self.0.push_front(&burst[len..]);
+        }
+        Ok(len)
+    }
+}
+
+#[test]
+fn a_lone_escape_does_not_swallow_the_next_key() {
+    let mut input = Bursts(VecDeque::from([
+        &b"\x1b"[..],
+        b"",
+        b"a",
+        b"\x1b",
+        b"",
+        b"\x1b[B",
+    ]));
+    assert_eq!(read_key(&mut input).unwrap(), Some(Key::Escape));
+    assert_eq!(read_key(&mut input).unwrap(), Some(Key::Char('a')));
+    assert_eq!(read_key(&mut input).unwrap(), Some(Key::Escape));
+    assert_eq!(read_key(&mut input).unwrap(), Some(Key::Down));
+}
@@ -0,0 +1,162 @@
+//! Native functions registered from Rust and called from scripts.
+
+use kestrl::{
+    interpreter::{
+        callable::Arity,
+        convert::{FromArgs, FromValue},
+        interpreter::{RuntimeError, Value},
+    },
+    kestrl::{Kestrl, KestrlError},
+};
+
+fn eval(kestrl: &mut Kestrl, source: &str) -> Value {
+    kestrl.eval(source).unwrap()
+}
+
+fn runtime_error(kestrl: &mut Kestrl, source: &str) -> String {
+    match kestrl.eval(source) {
+        Err(KestrlError::Runtime(diagnostic)) => {
+            let span = diagnostic.span.unwrap();
+            format!("{}:{} {}", span.line, span.column, diagnostic.message)
+        }
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+}
+
+#[test]
+fn clock_is_built_in() {
+    let mut kestrl = Kestrl::new("");
+    let Value::Number(now) = eval(&mut kestrl, "clock();") else {
+        panic!("clock() should return a number");
+    };
+    assert!(now > 1_600_000_000.0);
+    assert_eq!(
+        eval(&mut kestrl, "clock() - clock() <= 0;"),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn raw_natives_receive_the_argument_slice() {
+    let mut kestrl = Kestrl::new("");
+    kestrl.define_native("count", Arity::Variadic, |args| {
+        Ok(Value::Number(args.len() as f64))
+    });
+    kestrl.define_native("first", 2, |args| Ok(args[0].clone()));
+
+    assert_eq!(eval(&mut kestrl, "count();"), Value::Number(0.0));
+    assert_eq!(
+        eval(&mut kestrl, "count(1, nil, \"x\");"),
+        Value::Number(3.0)
+    );
+    assert_eq!(eval(&mut kestrl, "first(true, 1);"), Value::Boolean(true));
+    assert_eq!(
+        runtime_error(&mut kestrl, "first(1);"),
+        "1:8 Expected 2 arguments but got 1."
+    );
+}
+
+#[test]
+fn typed_natives_convert_arguments_and_results() {
+    let mut kestrl = Kestrl::new("");
+    kestrl.define_fn("hypot", |(a, b): (f64, f64)| Ok(a.hypot(b)));
+    kestrl.define_fn("shout", |(s,): (String,)| Ok(s.to_uppercase()));
+    kestrl.define_fn("not", |(b,): (bool,)| Ok(!b));
+    kestrl.define_fn("sum", |numbers: Vec<f64>| Ok(numbers.iter().sum::<f64>()));
+    kestrl.define_fn("or_default", |(value,): (Option<String>,)| {
+        Ok(value.unwrap_or_else(|| "default".to_string()))
+    });
+    kestrl.define_fn("maybe", |(n,): (f64,)| Ok((n > 0.0).then_some(n)));
+
+    assert_eq!(eval(&mut kestrl, "hypot(3, 4);"), Value::Number(5.0));
+    assert_eq!(eval(&mut kestrl, "shout(\"hey\");"), Value::from("HEY"));
+    assert_eq!(eval(&mut kestrl, "not(false);"), Value::Boolean(true));
+    assert_eq!(eval(&mut kestrl, "sum();"), Value::Number(0.0));
+    assert_eq!(eval(&mut kestrl, "sum(1, 2, 3.5);"), Value::Number(6.5));
+    assert_eq!(
+        eval(&mut kestrl, "or_default(nil);"),
+        Value::from("default")
+    );
+    assert_eq!(
+        eval(&mut kestrl, "or_default(\"set\");"),
+        Value::from("set")
+    );
+    assert_eq!(eval(&mut kestrl, "maybe(-1);"), Value::Nil);
+    assert_eq!(eval(&mut kestrl, "maybe(2);"), Value::Number(2.0));
+}
+
+#[test]
+fn native_errors_point_at_the_call() {
+    let mut kestrl = Kestrl::new("");
+    kestrl.define_fn("sqrt", |(n,): (f64,)| {
+        if n < 0.0 {
+            Err(RuntimeError::new(
+                "Can't take the square root of a negative number.",
+            ))
+        } else {
+            Ok(n.sqrt())
+        }
+    });
+    kestrl.define_fn("sum", |numbers: Vec<f64>| Ok(numbers.iter().sum::<f64>()));
+
+    assert_eq!(
+        runtime_error(&mut kestrl, "var x =\n  sqrt(-4);"),
+        "2:3 Can't take the square root of a negative number."
+    );
+    assert_eq!(
+        runtime_error(&mut kestrl, "sqrt(\"4\");"),
+        "1:1 Expected a number but got a string."
+    );
+    assert_eq!(
+        runtime_error(&mut kestrl, "sum(1, nil);"),
+        "1:1 Expected a number but got nil."
+    );
+}
+
+#[test]
+fn natives_are_first_class_values() {
+    let mut kestrl = Kestrl::new("");
+    kestrl.define_fn("double", |(n,): (f64,)| Ok(n * 2.0));
+
+    assert_eq!(
+        eval(
+            &mut kestrl,
+            "fun apply(f, x) { return f(x); } apply(double, 21);"
+        ),
+        Value::Number(42.0)
+    );
+}
+
+#[test]
+fn conversions_round_trip() {
+    assert_eq!(f64::from_value(&Value::from(1.5)).unwrap(), 1.5);
+    assert!(bool::from_value(&Value::from(true)).unwrap());
+    assert_eq!(String::from_value(&Value::from("s")).unwrap(), "s");
+    assert_eq!(
+        Option::<f64>::from_value(&Value::from(None::<f64>)).unwrap(),
+        None
+    );
+    assert_eq!(Value::from(()), Value::Nil);
+    assert_eq!(
+        <(f64, Option<String>)>::from_args(&[Value::from(1.0), Value::Nil]).unwrap(),
+        (1.0, None)
+    );
+    assert_eq!(
+        Vec::<String>::from_args(&[Value::from("a"), Value::from("b")]).unwrap(),
+        ["a", "b"]
+    );
+    assert_eq!(
+        <(f64, f64)>::from_args(&[Value::from(1.0)])
+            .unwrap_err()
+            .message,
+        "Expected 2 arguments but got 1."
+    );
+    assert!(f64::from_value(&Value::from("1")).is_err());
+
+    assert_eq!(i64::from_value(&Value::from(7)).unwrap(), 7);
+    assert_eq!(f64::from_value(&Value::from(7)).unwrap(), 7.0);
+    assert_eq!(
+        i64::from_value(&Value::from(7.5)).unwrap_err().message,
+        "Expected an integer but got a number."
+    );
+}
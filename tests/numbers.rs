@@ -0,0 +1,167 @@
+//! Number literal forms, the diagnostics for malformed ones, and integer and
+//! float arithmetic.
+
+use kestrl::{interpreter::interpreter::Value, kestrl::Kestrl};
+
+fn eval(source: &str) -> Value {
+    Kestrl::new(source).run().unwrap()
+}
+
+/// The REPL form of the result, which tells `2` and `2.0` apart.
+fn repr(source: &str) -> String {
+    eval(source).repr()
+}
+
+fn runtime_error(source: &str) -> (usize, String) {
+    let error = Kestrl::new(source).run().unwrap_err();
+    let diagnostic = &error.diagnostics()[0];
+    (diagnostic.span.unwrap().column, diagnostic.message.clone())
+}
+
+fn scan_error(source: &str) -> (usize, usize, String) {
+    let error = Kestrl::scan(source, 1).unwrap_err();
+    let diagnostics = error.diagnostics();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let span = diagnostics[0].span.unwrap();
+    (
+        span.column,
+        span.end - span.start,
+        diagnostics[0].message.clone(),
+    )
+}
+
+#[test]
+fn radix_literals() {
+    assert_eq!(eval("0xFF;"), Value::Int(255));
+    assert_eq!(eval("0Xff;"), Value::Int(255));
+    assert_eq!(eval("0b1010;"), Value::Int(10));
+    assert_eq!(eval("0o755;"), Value::Int(493));
+    assert_eq!(eval("0xFFFF_FFFF;"), Value::Int(4_294_967_295));
+    assert_eq!(eval("0b1111_0000;"), Value::Int(240));
+}
+
+#[test]
+fn decimal_literals_with_exponents_and_separators() {
+    assert_eq!(eval("1e-9;"), Value::Number(1e-9));
+    assert_eq!(eval("2.5E3;"), Value::Number(2500.0));
+    assert_eq!(eval("1e+2;"), Value::Number(100.0));
+    assert_eq!(eval("1_000_000;"), Value::Int(1_000_000));
+    assert_eq!(eval("1.234_5;"), Value::Number(1.234_5));
+    assert_eq!(eval("1_0e1_0;"), Value::Number(1e11));
+    assert_eq!(eval("007;"), Value::Int(7));
+}
+
+#[test]
+fn a_trailing_dot_is_not_part_of_the_number() {
+    let tokens = Kestrl::scan("1.e5", 1).unwrap();
+    let lexemes: Vec<&str> = tokens.iter().map(|token| token.lexeme.as_str()).collect();
+    assert_eq!(lexemes, ["1", ".", "e5", ""]);
+}
+
+#[test]
+fn malformed_literals_are_diagnostics() {
+    let cases = [
+        ("0x", 2, "Expected hexadecimal digits after '0x'."),
+        ("0b", 2, "Expected binary digits after '0b'."),
+        ("0o_", 3, "Expected octal digits after '0o'."),
+        ("1e", 2, "Expected digits after the exponent."),
+        ("1.5e-", 5, "Expected digits after the exponent."),
+        ("0xFG", 4, "Invalid digit 'G' in hexadecimal literal."),
+        ("0b102", 5, "Invalid digit '2' in binary literal."),
+        ("0o78", 4, "Invalid digit '8' in octal literal."),
+        ("1_", 2, "Misplaced '_' in number literal."),
+        ("1_.5", 2, "Misplaced '_' in number literal."),
+        ("1e_5", 4, "Misplaced '_' in number literal."),
+        ("0x_1", 4, "Misplaced '_' in number literal."),
+        ("12px", 4, "Invalid suffix 'px' on number literal."),
+        ("1e400", 5, "Number literal is too large."),
+        ("9223372036854775808", 19, "Integer literal is too large."),
+        (
+            "0x8000000000000000",
+            18,
+            "Hexadecimal literal is too large.",
+        ),
+    ];
+    for (literal, len, message) in cases {
+        assert_eq!(
+            scan_error(&format!("print {literal};")),
+            (7, len, message.to_string()),
+            "{literal}"
+        );
+    }
+}
+
+#[test]
+fn literals_without_a_fraction_or_exponent_are_integers() {
+    assert_eq!(repr("1;"), "1");
+    assert_eq!(repr("1.0;"), "1.0");
+    assert_eq!(repr("1e3;"), "1000.0");
+    assert_eq!(repr("9223372036854775807;"), "9223372036854775807");
+    assert_eq!(eval("1;").type_name(), "integer");
+    assert_eq!(eval("1.0;").type_name(), "number");
+}
+
+#[test]
+fn integer_arithmetic_is_exact() {
+    assert_eq!(repr("9007199254740992 + 1;"), "9007199254740993");
+    assert_eq!(repr("3037000499 * 3037000499;"), "9223372030926249001");
+    assert_eq!(repr("2 - 5;"), "-3");
+    assert_eq!(repr("-(2 * 3);"), "-6");
+}
+
+#[test]
+fn mixing_integers_and_floats_gives_floats() {
+    assert_eq!(repr("1 + 2.5;"), "3.5");
+    assert_eq!(repr("2.0 * 3;"), "6.0");
+    assert_eq!(repr("1 == 1.0;"), "true");
+    assert_eq!(repr("1 != 1.0;"), "false");
+    // Rust-side equality is exact about the type.
+    assert_ne!(Value::Int(1), Value::Number(1.0));
+    assert_eq!(repr("2 < 2.5;"), "true");
+    assert_eq!(repr("3 >= 3.0;"), "true");
+}
+
+#[test]
+fn division_floor_division_and_modulo() {
+    assert_eq!(repr("7 / 2;"), "3.5");
+    assert_eq!(repr("4 / 2;"), "2.0");
+    assert_eq!(repr("7 ~/ 2;"), "3");
+    assert_eq!(repr("-7 ~/ 2;"), "-4");
+    assert_eq!(repr("7 ~/ -2;"), "-4");
+    assert_eq!(repr("-7.5 ~/ 2;"), "-4.0");
+    assert_eq!(repr("7 % 3;"), "1");
+    assert_eq!(repr("-7 % 3;"), "2");
+    assert_eq!(repr("7 % -3;"), "-2");
+    assert_eq!(repr("7.5 % 2;"), "1.5");
+    assert_eq!(repr("1 + 7 % 4 * 2;"), "7");
+    assert_eq!(repr("// a comment, not floor division\n8 ~/ 3;"), "2");
+}
+
+#[test]
+fn integer_overflow_and_division_by_zero_are_runtime_errors() {
+    let overflow = "Integer overflow.".to_string();
+    let by_zero = "Division by zero.".to_string();
+    assert_eq!(
+        runtime_error("9223372036854775807 + 1;"),
+        (21, overflow.clone())
+    );
+    assert_eq!(
+        runtime_error("var m = -9223372036854775807 - 1; m * -1;"),
+        (37, overflow.clone())
+    );
+    assert_eq!(
+        runtime_error("var m = -9223372036854775807 - 1; -m;"),
+        (35, overflow.clone())
+    );
+    assert_eq!(
+        runtime_error("var m = -9223372036854775807 - 1; m ~/ -1;"),
+        (37, overflow)
+    );
+    assert_eq!(runtime_error("1 / 0;"), (3, by_zero.clone()));
+    assert_eq!(runtime_error("1 ~/ 0;"), (3, by_zero.clone()));
+    assert_eq!(runtime_error("1 % 0.0;"), (3, by_zero));
+    assert_eq!(
+        runtime_error("1 % \"a\";"),
+        (3, "Operands must be numbers.".to_string())
+    );
+}
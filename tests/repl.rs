@@ -0,0 +1,99 @@
+//! How the REPL reads its `:` commands and decides when an input is ready
+//! to run.
+
+use kestrl::repl::{Command, is_complete};
+
+#[test]
+fn commands_are_parsed_with_their_argument() {
+    assert_eq!(Command::parse(":help"), Ok(Command::Help));
+    assert_eq!(Command::parse("  :reset  \n"), Ok(Command::Reset));
+    assert_eq!(Command::parse(":env"), Ok(Command::Env));
+    assert_eq!(
+        Command::parse(":load  scripts/demo.kst\n"),
+        Ok(Command::Load("scripts/demo.kst"))
+    );
+    assert_eq!(
+        Command::parse(":ast print 1 + 2;"),
+        Ok(Command::Ast("print 1 + 2;"))
+    );
+    assert_eq!(
+        Command::parse(":tokens var a;"),
+        Ok(Command::Tokens("var a;"))
+    );
+    assert_eq!(Command::parse(":time f();"), Ok(Command::Time("f();")));
+}
+
+#[test]
+fn missing_arguments_show_the_usage() {
+    assert_eq!(
+        Command::parse(":load"),
+        Err("usage: :load <file>".to_string())
+    );
+    assert_eq!(
+        Command::parse(":load   \n"),
+        Err("usage: :load <file>".to_string())
+    );
+    assert_eq!(
+        Command::parse(":ast"),
+        Err("usage: :ast <code>".to_string())
+    );
+    assert_eq!(
+        Command::parse(":tokens"),
+        Err("usage: :tokens <code>".to_string())
+    );
+    assert_eq!(
+        Command::parse(":time"),
+        Err("usage: :time <code>".to_string())
+    );
+}
+
+#[test]
+fn unknown_commands_are_rejected() {
+    assert_eq!(
+        Command::parse(":quit"),
+        Err("unknown command ':quit', type :help for a list of commands".to_string())
+    );
+    assert_eq!(
+        Command::parse(":astprint 1;"),
+        Err("unknown command ':astprint', type :help for a list of commands".to_string())
+    );
+}
+
+#[test]
+fn open_brackets_keep_reading() {
+    assert!(!is_complete("fun f() {\n"));
+    assert!(!is_complete("print (1 +\n"));
+    assert!(!is_complete("if (true) {\n  print [1, (2\n"));
+    assert!(is_complete("fun f() {\n  return 1;\n}\n"));
+    assert!(is_complete("print (1 + 2);\n"));
+}
+
+#[test]
+fn unterminated_strings_keep_reading() {
+    assert!(!is_complete("print \"hello\n"));
+    assert!(!is_complete("print \"a ${1 +\n"));
+    assert!(!is_complete("print \"a ${1 + 2}\n"));
+    assert!(is_complete("print \"a ${1 + 2} b\";\n"));
+}
+
+#[test]
+fn a_missing_semicolon_keeps_reading() {
+    assert!(!is_complete("print 1\n"));
+    assert!(!is_complete("var a = 1\n"));
+    assert!(is_complete("print 1;\n"));
+}
+
+#[test]
+fn errors_not_caused_by_the_end_of_input_submit() {
+    assert!(is_complete("}\n"));
+    assert!(is_complete("print 1; }\n"));
+    assert!(is_complete("var = 1;\n"));
+    assert!(is_complete("print @;\n"));
+}
+
+#[test]
+fn comments_and_blank_lines_are_complete() {
+    assert!(is_complete("// just a note\n"));
+    assert!(is_complete("\n"));
+    assert!(is_complete(""));
+}
@@ -0,0 +1,89 @@
+//! Variable binding and the scope errors reported by the resolver before a
+//! script runs.
+
+use kestrl::{
+    interpreter::output::Buffer,
+    kestrl::{Kestrl, KestrlError},
+};
+
+/// Everything the script prints.
+fn output(source: &str) -> String {
+    let output = Buffer::new();
+    let mut kestrl = Kestrl::new(source);
+    kestrl.set_output(output.clone());
+    kestrl.run().unwrap();
+    output.contents()
+}
+
+fn resolve_errors(source: &str) -> Vec<String> {
+    match Kestrl::new(source).run() {
+        Err(KestrlError::Resolve(diagnostics)) => diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect(),
+        other => panic!("expected resolve errors, got {other:?}"),
+    }
+}
+
+#[test]
+fn closures_keep_the_binding_they_were_resolved_to() {
+    let source = "var a = \"global\";
+                  {
+                      fun showA() { print a; }
+                      showA();
+                      var a = \"block\";
+                      showA();
+                  }";
+    assert_eq!(output(source), "global\nglobal\n");
+}
+
+#[test]
+fn locals_cannot_be_read_in_their_own_initializer() {
+    assert_eq!(
+        resolve_errors("var a = 1; { var a = a + 1; }"),
+        ["Can't read local variable in its own initializer."]
+    );
+    // A global initializer is evaluated before the name is bound.
+    assert!(Kestrl::new("var b = 1; var b = b + 1;").run().is_ok());
+}
+
+#[test]
+fn this_is_only_valid_inside_a_class() {
+    assert_eq!(
+        resolve_errors("print this;"),
+        ["Can't use 'this' outside of a class."]
+    );
+    assert_eq!(
+        resolve_errors("fun f() { return this; }"),
+        ["Can't use 'this' outside of a class."]
+    );
+}
+
+#[test]
+fn super_needs_a_superclass() {
+    assert_eq!(
+        resolve_errors("class A { f() { return super.f(); } }"),
+        ["Can't use 'super' in a class with no superclass."]
+    );
+    assert_eq!(
+        resolve_errors("fun f() { super.g(); }"),
+        ["Can't use 'super' outside of a class."]
+    );
+}
+
+#[test]
+fn locals_cannot_be_declared_twice_in_one_scope() {
+    assert_eq!(
+        resolve_errors("{ var a = 1; var a = 2; }"),
+        ["Already a variable with this name in this scope."]
+    );
+    assert_eq!(
+        resolve_errors("fun f(a, a) {}"),
+        ["Already a variable with this name in this scope."]
+    );
+    // Shadowing in an inner scope is fine.
+    assert_eq!(
+        output("{ var a = 1; { var a = 2; print a; } print a; }"),
+        "2\n1\n"
+    );
+}
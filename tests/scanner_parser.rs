@@ -0,0 +1,266 @@
+//! Property-style checks for the front end: whatever text is fed to the
+//! `Scanner`, the `Scanner` -> `Parser` -> `Resolver` pipeline must produce
+//! tokens or diagnostics, never a panic.
+
+use kestrl::{
+    interpreter::{
+        diagnostics::Diagnostic,
+        parser::{MAX_NESTING, Parser},
+        resolver::Resolver,
+        scanner::Scanner,
+        token_types::TokenType,
+    },
+    kestrl::with_stack,
+};
+
+const CASES: usize = 2_000;
+
+/// Fragments that are likely to form interesting (and interestingly broken)
+/// programs when glued together at random.
+const FRAGMENTS: &[&str] = &[
+    "var", "fun", "class", "return", "if", "else", "while", "for", "print", "this", "super", "and",
+    "or", "nil", "true", "false", "x", "y", "init", "(", ")", "{", "}", ",", ".", ";", "-", "+",
+    "*", "/", "!", "!=", "=", "==", "<", "<=", ">", ">=", "<", "\"", "\"str\"", "1", "2.5", "3.",
+    "//", "\n", " ", "\t", "\r", "é", "λ", "😀", "@", "#", "\0", "\\", "\\n", "\\u{", "\\u{41}",
+    "}", "\"\"\"", "${", "\"a${", "}b\"", "0x", "0b1", "0o7", "1e", "e-", "_", "1_0", "2n", "1.5d",
+    "n", "d",
+];
+
+/// Small deterministic xorshift generator so failures are reproducible
+/// without pulling in a property-testing crate.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+fn random_program(rng: &mut Rng) -> String {
+    let len = rng.below(40);
+    let mut source = String::new();
+    for _ in 0..len {
+        if rng.below(8) == 0 {
+            // Occasionally splice in an arbitrary character, including control
+            // characters and non-ASCII code points.
+            let c = char::from_u32(rng.below(0x1_0000) as u32).unwrap_or('?');
+            source.push(c);
+        } else {
+            source.push_str(FRAGMENTS[rng.below(FRAGMENTS.len())]);
+        }
+        if rng.below(2) == 0 {
+            source.push(' ');
+        }
+    }
+    source
+}
+
+fn run_front_end(source: &str) {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut reporter = |diagnostic: Diagnostic| diagnostics.push(diagnostic);
+
+    let tokens = Scanner::new(source).scan_tokens(&mut reporter);
+    assert_token_stream_is_well_formed(source, &tokens);
+
+    if let Ok(statements) = Parser::new(tokens).parse() {
+        Resolver::new(&mut reporter).resolve(&statements);
+    }
+
+    for diagnostic in &diagnostics {
+        diagnostic.render(source, 1, false);
+    }
+}
+
+fn assert_token_stream_is_well_formed(
+    source: &str,
+    tokens: &[kestrl::interpreter::scanner::Token],
+) {
+    let eof_count = tokens
+        .iter()
+        .filter(|token| token.token_type == TokenType::EOF)
+        .count();
+    assert_eq!(eof_count, 1, "expected exactly one EOF in {source:?}");
+
+    let last = tokens.last().expect("token stream is never empty");
+    assert_eq!(
+        last.token_type,
+        TokenType::EOF,
+        "EOF must be last in {source:?}"
+    );
+    assert_eq!(last.span.start, source.len());
+    assert_eq!(last.span.end, source.len());
+
+    let mut previous_end = 0;
+    for token in tokens {
+        assert!(token.span.start <= token.span.end);
+        assert!(
+            token.span.start >= previous_end,
+            "overlapping spans in {source:?}"
+        );
+        assert!(token.span.end <= source.len());
+        assert_eq!(&source[token.span.start..token.span.end], token.lexeme);
+        assert!(token.span.line >= 1 && token.span.column >= 1);
+        previous_end = token.span.end;
+    }
+}
+
+#[test]
+fn random_inputs_never_panic() {
+    let mut rng = Rng(0x5eed_1234_abcd_ef01);
+    for _ in 0..CASES {
+        let source = random_program(&mut rng);
+        run_front_end(&source);
+    }
+}
+
+/// Openers that each add a level of recursion to the parser and resolver.
+const NESTERS: &[&str] = &[
+    "(",
+    "-",
+    "!",
+    "{",
+    "\"${",
+    "f(",
+    "a = ",
+    "if (x) ",
+    "while (x) ",
+    "fun f() {",
+    "if (x) x; else ",
+];
+
+#[test]
+fn deeply_nested_inputs_never_panic() {
+    with_stack(|| {
+        for opener in NESTERS {
+            run_front_end(&opener.repeat(5_000));
+            run_front_end(&format!("print {}1;", opener.repeat(5_000)));
+        }
+
+        let mut rng = Rng(0xdee9_0000_1e57_0001);
+        for _ in 0..20 {
+            let source: String = (0..5_000)
+                .map(|_| NESTERS[rng.below(NESTERS.len())])
+                .collect();
+            run_front_end(&source);
+        }
+    });
+}
+
+#[test]
+fn every_prefix_of_a_valid_program_is_handled() {
+    let program = "class A < B { init(x) { this.x = x; } get() { return super.get() + this.x; } }\n\
+                   fun f(a, b) { for (var i = 0; i < a; i = i + 1) { if (i == b) print i; else print \"no\"; } }\n\
+                   var λ = f(1, 2) or !nil and 3.5 >= -1;";
+    for (end, _) in program.char_indices() {
+        run_front_end(&program[..end]);
+    }
+    run_front_end(program);
+}
+
+#[test]
+fn empty_input_is_a_single_eof() {
+    let mut reporter = |_: Diagnostic| {};
+    let tokens = Scanner::new("").scan_tokens(&mut reporter);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token_type, TokenType::EOF);
+    assert_eq!((tokens[0].span.line, tokens[0].span.column), (1, 1));
+}
+
+#[test]
+fn eof_is_positioned_after_the_last_character() {
+    let mut reporter = |_: Diagnostic| {};
+    let tokens = Scanner::new("print 1;\n  x").scan_tokens(&mut reporter);
+    let eof = tokens.last().unwrap();
+    assert_eq!(eof.token_type, TokenType::EOF);
+    assert_eq!((eof.span.line, eof.span.column), (2, 4));
+    assert_eq!(eof.span.start, 12);
+}
+
+#[test]
+fn eof_errors_are_reported_on_the_last_line_with_code() {
+    let source = "var a = 1;\nprint a\n\n";
+    let mut reporter = |_: Diagnostic| {};
+    let tokens = Scanner::new(source).scan_tokens(&mut reporter);
+    let eof = tokens.last().unwrap();
+    assert_eq!((eof.span.line, eof.span.column), (2, 8));
+    assert_eq!(eof.span.start, source.len());
+
+    let errors = Parser::new(tokens).parse().unwrap_err();
+    let diagnostic = Diagnostic::from(errors[0].clone());
+    assert_eq!(diagnostic.message, "Expect ';' after value.");
+    let rendered = diagnostic.render(source, 1, false);
+    assert!(rendered.contains("2 | print a\n"), "{rendered}");
+}
+
+#[test]
+fn unterminated_constructs_report_instead_of_panicking() {
+    for source in [
+        "(",
+        "print",
+        "var x =",
+        "fun f(",
+        "class A {",
+        "{",
+        "1 +",
+        "a.",
+        "super",
+    ] {
+        let mut reporter = |_: Diagnostic| {};
+        let tokens = Scanner::new(source).scan_tokens(&mut reporter);
+        assert!(
+            Parser::new(tokens).parse().is_err(),
+            "{source:?} should not parse"
+        );
+    }
+}
+
+#[test]
+fn parser_accepts_a_token_list_without_eof() {
+    let mut reporter = |_: Diagnostic| {};
+    let mut tokens = Scanner::new("print 1").scan_tokens(&mut reporter);
+    tokens.pop();
+    assert!(Parser::new(tokens).parse().is_err());
+    assert!(Parser::new(Vec::new()).parse().unwrap().is_empty());
+}
+
+#[test]
+fn nesting_past_the_limit_is_a_parse_error() {
+    let parse = |source: &str| {
+        let mut reporter = |_: Diagnostic| {};
+        Parser::new(Scanner::new(source).scan_tokens(&mut reporter)).parse()
+    };
+    let depth = MAX_NESTING - 1;
+    let parens = |n| format!("print {}1{};", "(".repeat(n), ")".repeat(n));
+    let blocks = |n| format!("{}{}", "{".repeat(n), "}".repeat(n));
+    let too_deep = MAX_NESTING * 4;
+
+    with_stack(|| {
+        assert!(parse(&parens(depth)).is_ok());
+        assert!(parse(&blocks(MAX_NESTING)).is_ok());
+
+        // A flat `else if` chain is not nesting, however long it is.
+        let chain: String = (0..MAX_NESTING * 4)
+            .map(|i| format!("if (x == {i}) print {i}; else "))
+            .collect();
+        assert!(parse(&format!("{chain}print nil;")).is_ok());
+
+        for source in [
+            parens(too_deep),
+            format!("print {}1;", "-".repeat(too_deep)),
+            blocks(too_deep),
+            format!("{}print 1;", "while (true) ".repeat(too_deep)),
+            format!("{}print 1;", "if (x) x; else {".repeat(too_deep)),
+        ] {
+            let errors = parse(&source).unwrap_err();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].message, "Too much nesting.");
+        }
+    });
+}
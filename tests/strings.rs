@@ -0,0 +1,223 @@
+//! String literal escapes, line continuations, triple-quoted strings and
+//! `${...}` interpolation.
+
+use kestrl::{
+    interpreter::{
+        diagnostics::Diagnostic,
+        interpreter::Value,
+        scanner::{Literal, Scanner},
+        token_types::TokenType,
+    },
+    kestrl::Kestrl,
+};
+
+/// Scans `source`, which must be a single string literal, returning its
+/// value and any diagnostics.
+fn scan_string(source: &str) -> (Option<String>, Vec<Diagnostic>) {
+    let mut diagnostics = Vec::new();
+    let mut reporter = |diagnostic: Diagnostic| diagnostics.push(diagnostic);
+    let tokens = Scanner::new(source).scan_tokens(&mut reporter);
+    let value = tokens
+        .iter()
+        .find_map(|token| match (&token.token_type, &token.literal) {
+            (TokenType::STRING, Literal::Str(value)) => Some(value.clone()),
+            _ => None,
+        });
+    (value, diagnostics)
+}
+
+fn string_value(source: &str) -> String {
+    let (value, diagnostics) = scan_string(source);
+    assert_eq!(diagnostics, [], "{source}");
+    value.unwrap()
+}
+
+#[test]
+fn escapes_are_translated() {
+    assert_eq!(string_value(r#""a\nb""#), "a\nb");
+    assert_eq!(string_value(r#""\t\r\0""#), "\t\r\0");
+    assert_eq!(string_value(r#""say \"hi\"""#), "say \"hi\"");
+    assert_eq!(string_value(r#""C:\\path""#), "C:\\path");
+    assert_eq!(string_value(r#""\u{41}\u{e9}\u{1F600}""#), "Aé😀");
+}
+
+#[test]
+fn whitespace_is_kept() {
+    assert_eq!(string_value("\"  two  spaces  \""), "  two  spaces  ");
+    assert_eq!(
+        string_value("\"line one\n  line two\""),
+        "line one\n  line two"
+    );
+}
+
+#[test]
+fn backslash_newline_continues_the_line() {
+    let haiku = "\"While testing Kestrl \\\n            Pain and suffering \\\r\n\tunavoidable.\"";
+    assert_eq!(
+        string_value(haiku),
+        "While testing Kestrl Pain and suffering unavoidable."
+    );
+}
+
+#[test]
+fn triple_quoted_strings_are_verbatim() {
+    assert_eq!(
+        string_value("\"\"\"raw \\n \"quoted\" \\\n  next\"\"\""),
+        "raw \\n \"quoted\" \\\n  next"
+    );
+    assert_eq!(string_value("\"\"\"\"\"\""), "");
+}
+
+#[test]
+fn tokens_after_multi_line_strings_have_correct_positions() {
+    let mut reporter = |diagnostic: Diagnostic| panic!("{diagnostic:?}");
+    let tokens = Scanner::new("\"a\\\n  b\" \"\"\"c\nd\"\"\" x").scan_tokens(&mut reporter);
+    let x = &tokens[2];
+    assert_eq!(x.lexeme, "x");
+    assert_eq!((x.span.line, x.span.column), (3, 6));
+}
+
+#[test]
+fn invalid_escapes_are_reported_where_they_occur() {
+    let (_, diagnostics) = scan_string("\"ok \\q then \\u{110000} \\u41 \\u{}\"");
+    let found: Vec<(usize, usize, &str)> = diagnostics
+        .iter()
+        .map(|d| {
+            let span = d.span.unwrap();
+            (span.column, span.end - span.start, d.message.as_str())
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (5, 2, "Unknown escape sequence '\\q'."),
+            (13, 10, "'110000' is not a valid Unicode code point."),
+            (24, 2, "Invalid Unicode escape."),
+            (29, 4, "Invalid Unicode escape."),
+        ]
+    );
+    assert!(diagnostics.iter().all(|d| !d.unexpected_eof));
+}
+
+#[test]
+fn unterminated_strings_are_reported_at_eof() {
+    for source in ["\"open", "\"ends in backslash\\", "\"\"\"raw \" \"\""] {
+        let (value, diagnostics) = scan_string(source);
+        assert_eq!(value, None, "{source}");
+        assert_eq!(diagnostics.len(), 1, "{source}");
+        assert_eq!(diagnostics[0].message, "Unterminated string.");
+        assert!(diagnostics[0].unexpected_eof, "{source}");
+    }
+}
+
+fn eval(source: &str) -> Value {
+    Kestrl::new(source).run().unwrap()
+}
+
+fn first_error(source: &str) -> (usize, usize, String) {
+    let error = Kestrl::new(source).run().unwrap_err();
+    let diagnostic = &error.diagnostics()[0];
+    let span = diagnostic.span.unwrap();
+    (
+        span.column,
+        span.end - span.start,
+        diagnostic.message.clone(),
+    )
+}
+
+#[test]
+fn interpolation_formats_values_like_print() {
+    assert_eq!(
+        eval("var name = \"Ada\"; var age = 36; \"Hello ${name}, you are ${age + 1}\";"),
+        Value::from("Hello Ada, you are 37")
+    );
+    assert_eq!(
+        eval("fun f() {} class C {} \"${nil} ${true} ${2.5} ${f} ${C} ${C()}\";"),
+        Value::from("nil true 2.5 <fn f> C C instance")
+    );
+    assert_eq!(eval("\"${1}${2}\";"), Value::from("12"));
+    assert_eq!(eval("\"${\"only\"}\";"), Value::from("only"));
+}
+
+#[test]
+fn interpolation_nests_and_respects_escapes() {
+    assert_eq!(
+        eval("\"a ${ \"b ${ \"c\" + \"d\" } \\\"e\\\"\" } f\";"),
+        Value::from("a b cd \"e\" f")
+    );
+    assert_eq!(
+        eval("fun f() { if (true) { return \"in braces\"; } } \"${ f() }\";"),
+        Value::from("in braces")
+    );
+    assert_eq!(
+        eval("\"\\${not} $5 ${\"$\"}\";"),
+        Value::from("${not} $5 $")
+    );
+    assert_eq!(eval("\"\"\"raw ${x}\"\"\";"), Value::from("raw ${x}"));
+    assert_eq!(
+        eval("\"line ${\n1 +\n2\n} end\";"),
+        Value::from("line 3 end")
+    );
+}
+
+#[test]
+fn interpolation_tokens_are_segments() {
+    let mut reporter = |diagnostic: Diagnostic| panic!("{diagnostic:?}");
+    let tokens = Scanner::new("\"a${x}b${y}c\"").scan_tokens(&mut reporter);
+    let summary: Vec<(TokenType, &str)> = tokens
+        .iter()
+        .map(|token| (token.token_type.clone(), token.lexeme.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (TokenType::INTERPOLATION, "\"a${"),
+            (TokenType::IDENTIFIER, "x"),
+            (TokenType::INTERPOLATION, "}b${"),
+            (TokenType::IDENTIFIER, "y"),
+            (TokenType::STRING, "}c\""),
+            (TokenType::EOF, ""),
+        ]
+    );
+}
+
+#[test]
+fn interpolation_errors_point_inside_the_string() {
+    assert_eq!(
+        first_error("print \"a ${} b\";"),
+        (12, 1, "Expect expression.".to_string())
+    );
+    assert_eq!(
+        first_error("print \"a ${1 + } b\";"),
+        (16, 1, "Expect expression.".to_string())
+    );
+    assert_eq!(
+        first_error("print \"a ${x y} b\";"),
+        (
+            14,
+            1,
+            "Expect '}' after interpolated expression.".to_string()
+        )
+    );
+    assert_eq!(
+        first_error("print \"a ${nope} b\";"),
+        (12, 4, "Undefined variable 'nope'.".to_string())
+    );
+    assert_eq!(
+        first_error("print \"a ${\"b\" - 1} c\";"),
+        (16, 1, "Operands must be numbers.".to_string())
+    );
+}
+
+#[test]
+fn unterminated_interpolations_are_reported_at_eof() {
+    let (_, diagnostics) = scan_string("print \"a ${1 + ");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Unterminated string interpolation.");
+    assert_eq!(diagnostics[0].span.unwrap().column, 10);
+    assert!(diagnostics[0].unexpected_eof);
+
+    let (_, diagnostics) = scan_string("\"a ${1} b");
+    assert_eq!(diagnostics[0].message, "Unterminated string.");
+    assert!(diagnostics[0].unexpected_eof);
+}